@@ -0,0 +1,67 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compare `MacAddress::parse_str` against the regex based parser it replaced
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use eui48::{Eui48, MacAddress, ParseError, EUI48LEN};
+use regex::Regex;
+
+const INPUTS: &[(&str, &str)] = &[
+    ("canonical", "12-34-56-AB-CD-EF"),
+    ("hex_string", "12:34:56:ab:cd:ef"),
+    ("dot_notation", "1234.56ab.cdef"),
+    ("hexadecimal", "0x123456abcdef"),
+    ("bare", "123456abcdef"),
+    ("invalid", "0x00:01:02:03:04:"),
+];
+
+/// The regex based parse_str shipped up to version 1.1.0
+fn regex_parse_str(s: &str) -> Result<MacAddress, ParseError> {
+    let re = Regex::new("(0x)?([0-9a-fA-F]{1,2})[:.-]?").unwrap();
+    let mut eui: Eui48 = [0; EUI48LEN];
+
+    match s.len() {
+        11..=17 => {}
+        _ => {
+            return Err(ParseError::InvalidLength(s.len()));
+        }
+    }
+
+    let mut i = 0;
+    for caps in re.captures_iter(s) {
+        if i < EUI48LEN {
+            let matched_byte = caps.get(2).unwrap().as_str();
+            eui[i] = u8::from_str_radix(matched_byte, 16).unwrap();
+        }
+        i += 1;
+    }
+
+    if i != EUI48LEN {
+        return Err(ParseError::InvalidByteCount(i, eui));
+    }
+
+    Ok(MacAddress::new(eui))
+}
+
+fn bench_parse_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_str");
+    for (name, input) in INPUTS {
+        assert_eq!(regex_parse_str(input), MacAddress::parse_str(input));
+        group.bench_with_input(BenchmarkId::new("hand_written", name), input, |b, s| {
+            b.iter(|| MacAddress::parse_str(black_box(s)))
+        });
+        group.bench_with_input(BenchmarkId::new("regex", name), input, |b, s| {
+            b.iter(|| regex_parse_str(black_box(s)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_str);
+criterion_main!(benches);
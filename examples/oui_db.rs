@@ -0,0 +1,113 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writes the table of the eui48-oui-db crate from IEEE registry files
+//!
+//! `cargo run --example oui_db -- oui.csv mam.csv oui36.csv iab.csv > oui-db/src/table.rs`
+
+extern crate eui48;
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+use eui48::{OuiRegistry, Registry};
+
+fn main() {
+    let mut registry = OuiRegistry::new();
+    for path in env::args().skip(1) {
+        if let Err(err) = registry.load_file(&path) {
+            eprintln!("{}: {}", path, err);
+            process::exit(1);
+        }
+    }
+
+    // Organizations and addresses repeat a lot, so each text is written once
+    let mut text = vec![""];
+    let mut index = HashMap::new();
+    index.insert("", 0);
+    let mut intern = |s| {
+        *index.entry(s).or_insert_with(|| {
+            text.push(s);
+            text.len() - 1
+        })
+    };
+
+    let mut assignments = Vec::new();
+    for vendor in &registry {
+        let code = match vendor.registry() {
+            Registry::MaL => "MA_L",
+            Registry::MaM => "MA_M",
+            Registry::MaS => "MA_S",
+            Registry::Iab => "IAB",
+            other => {
+                eprintln!(
+                    "{}: {} assignments are not supported",
+                    vendor.block(),
+                    other
+                );
+                process::exit(1);
+            }
+        };
+        let block = vendor.block();
+        let key = block.network().to_u64() << 8 | u64::from(block.prefix_len());
+        let organization = intern(vendor.organization());
+        let address = intern(vendor.address());
+        assignments.push((key, code, organization, address));
+    }
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    write_table(&mut out, &assignments, &text).expect("cannot write table");
+}
+
+fn write_table<W: Write>(
+    out: &mut W,
+    assignments: &[(u64, &str, usize, usize)],
+    text: &[&str],
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "// Generated by update.sh from the IEEE registries; do not edit"
+    )?;
+    writeln!(out)?;
+    writeln!(out, "/// Assignments sorted by block")?;
+    writeln!(out, "pub const ASSIGNMENTS: &[(u64, u8, u32, u32)] = &[")?;
+    for (key, code, organization, address) in assignments {
+        writeln!(
+            out,
+            "    ({:#016x}, {}, {}, {}),",
+            key, code, organization, address
+        )?;
+    }
+    writeln!(out, "];")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// Organization names and addresses of ASSIGNMENTS, one after another"
+    )?;
+    writeln!(out, "pub const TEXT: &str = \"\\")?;
+    for s in text.iter().skip(1) {
+        let quoted = format!("{:?}", s);
+        writeln!(out, "{}\\", &quoted[1..quoted.len() - 1])?;
+    }
+    writeln!(out, "\";")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// Offset in TEXT of the end of each text, the start of the next one"
+    )?;
+    writeln!(out, "pub const TEXT_ENDS: &[u32] = &[")?;
+    let mut end = 0;
+    for s in text {
+        end += s.len();
+        writeln!(out, "    {},", end)?;
+    }
+    writeln!(out, "];")?;
+    out.flush()
+}
@@ -0,0 +1,31 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The IEEE MA-L, MA-M, MA-S and IAB registries as a compact table
+//!
+//! This crate only carries data for the `oui-db` feature of the `eui48` crate, which turns it
+//! into `MacAddress::vendor()`; use that instead of the table. It is kept out of `eui48` so
+//! that the registries are only downloaded when the feature is enabled.
+//!
+//! `update.sh` regenerates the table from the files published by the IEEE.
+
+#![no_std]
+
+/// Registry code of an MA-L assignment, a 24-bit OUI
+pub const MA_L: u8 = 0;
+/// Registry code of an MA-M assignment, a 28-bit prefix
+pub const MA_M: u8 = 1;
+/// Registry code of an MA-S assignment, a 36-bit prefix
+pub const MA_S: u8 = 2;
+/// Registry code of an IAB assignment, a 36-bit prefix assigned before MA-S
+pub const IAB: u8 = 3;
+
+// Defines ASSIGNMENTS, holding `(network << 8 | prefix_len, registry, organization, address)`
+// sorted by block, and TEXT holding the texts one after another, with their end offsets in
+// TEXT_ENDS. The organization and address are indexes into TEXT_ENDS, where 0 is empty.
+include!("table.rs");
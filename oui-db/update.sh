@@ -0,0 +1,18 @@
+#!/bin/sh
+# Regenerates src/table.rs from the current IEEE registry files
+set -e
+cd "$(dirname "$0")"
+tmp=$(mktemp -d)
+trap 'rm -rf "$tmp"' EXIT
+
+for url in \
+    https://standards-oui.ieee.org/oui/oui.csv \
+    https://standards-oui.ieee.org/oui28/mam.csv \
+    https://standards-oui.ieee.org/oui36/oui36.csv \
+    https://standards-oui.ieee.org/iab/iab.csv
+do
+    curl -fsS -o "$tmp/${url##*/}" "$url"
+done
+cargo run --quiet --manifest-path ../Cargo.toml --example oui_db -- \
+    "$tmp/oui.csv" "$tmp/mam.csv" "$tmp/oui36.csv" "$tmp/iab.csv" > "$tmp/table.rs"
+mv "$tmp/table.rs" src/table.rs
@@ -0,0 +1,391 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Typed identifiers of the address blocks assigned by the IEEE, written as '00-1B-21'
+
+use core::fmt;
+use core::str::FromStr;
+
+use super::bitwise::from_bits;
+use super::display::{as_str, UPPER_HEX_DIGITS};
+use super::{MacAddress, MacPrefix, ParseError};
+
+macro_rules! assignment {
+    ($(#[$doc:meta])* $name:ident, $int:ty, $bits:expr) => {
+        $(#[$doc])*
+        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name($int);
+
+        impl $name {
+            /// Number of bits in the identifier
+            pub const BITS: u32 = $bits;
+
+            /// Returns the identifier as an integer
+            pub const fn value(&self) -> $int {
+                self.0
+            }
+
+            /// Returns the address made of the identifier followed by the low bits of `nic`
+            ///
+            /// Bits of `nic` that do not fit after the identifier are ignored.
+            pub const fn with_nic(&self, nic: u32) -> MacAddress {
+                let nic_mask = (1 << (48 - $bits)) - 1;
+                from_bits(((self.0 as u64) << (48 - $bits)) | (nic as u64 & nic_mask))
+            }
+
+            /// Returns the block of addresses starting with the identifier
+            pub const fn prefix(&self) -> MacPrefix {
+                match MacPrefix::new(self.with_nic(0), $bits) {
+                    Some(prefix) => prefix,
+                    None => panic!("invalid prefix length"),
+                }
+            }
+
+            /// Returns true if `mac` starts with the identifier
+            pub const fn contains(&self, mac: &MacAddress) -> bool {
+                mac.to_u64() >> (48 - $bits) == self.0 as u64
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseError;
+            /// Parses the hex digits of the identifier, optionally separated into pairs by
+            /// '-', ':' or '.'
+            fn from_str(s: &str) -> Result<$name, ParseError> {
+                let value = parse_digits(s, $bits / 4)?;
+                // The digit count bounds the value, so only a Cid can reject it here
+                $name::from_value(value as $int).ok_or(ParseError::InvalidCid(value as u32))
+            }
+        }
+
+        impl fmt::Display for $name {
+            /// Display format is pairs of uppercase hex digits separated by '-'
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let mut buf = [0; 13];
+                f.pad(write_digits(&mut buf, self.0 as u64, $bits / 4))
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}(\"{}\")", stringify!($name), self)
+            }
+        }
+    };
+}
+
+assignment!(
+    /// A 24-bit Organizationally Unique Identifier, the block of an MA-L assignment
+    ///
+    /// ```
+    /// use eui48::{MacAddress, Oui};
+    ///
+    /// let oui: Oui = "00-1B-21".parse().unwrap();
+    /// let mac = oui.with_nic(0x123456);
+    /// assert_eq!(MacAddress::new([0x00, 0x1b, 0x21, 0x12, 0x34, 0x56]), mac);
+    /// assert_eq!(oui, mac.oui());
+    /// assert_eq!(0x123456, mac.nic_specific());
+    /// assert_eq!("00-1B-21", oui.to_string());
+    /// ```
+    Oui,
+    u32,
+    24
+);
+assignment!(
+    /// A 28-bit MA-M assignment, written '70-B3-D5-1'
+    OuiMaM,
+    u32,
+    28
+);
+assignment!(
+    /// A 36-bit MA-S or IAB assignment, written '70-B3-D5-12-3'
+    OuiMaS,
+    u64,
+    36
+);
+assignment!(
+    /// A 24-bit Company ID, an identifier in the locally administered address space
+    ///
+    /// A Company ID always has the local bit set and the group bit clear.
+    Cid,
+    u32,
+    24
+);
+
+impl Oui {
+    /// Create an Oui from its three octets
+    pub const fn new(bytes: [u8; 3]) -> Oui {
+        Oui(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    /// Create an Oui from the low 24 bits of `value`
+    ///
+    /// Returns None if `value` does not fit in 24 bits.
+    pub const fn from_value(value: u32) -> Option<Oui> {
+        if value >> 24 != 0 {
+            return None;
+        }
+        Some(Oui(value))
+    }
+
+    /// Returns the three octets of the Oui
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        let b = self.0.to_be_bytes();
+        [b[1], b[2], b[3]]
+    }
+}
+
+impl OuiMaM {
+    /// Create an OuiMaM from the low 28 bits of `value`
+    ///
+    /// Returns None if `value` does not fit in 28 bits.
+    pub const fn from_value(value: u32) -> Option<OuiMaM> {
+        if value >> 28 != 0 {
+            return None;
+        }
+        Some(OuiMaM(value))
+    }
+
+    /// Returns the 24-bit block the assignment was carved from
+    pub const fn oui(&self) -> Oui {
+        Oui(self.0 >> 4)
+    }
+}
+
+impl OuiMaS {
+    /// Create an OuiMaS from the low 36 bits of `value`
+    ///
+    /// Returns None if `value` does not fit in 36 bits.
+    pub const fn from_value(value: u64) -> Option<OuiMaS> {
+        if value >> 36 != 0 {
+            return None;
+        }
+        Some(OuiMaS(value))
+    }
+
+    /// Returns the 24-bit block the assignment was carved from
+    pub const fn oui(&self) -> Oui {
+        Oui((self.0 >> 12) as u32)
+    }
+}
+
+impl Cid {
+    /// Create a Cid from its three octets
+    ///
+    /// Returns None unless the local bit is set and the group bit clear.
+    pub const fn new(bytes: [u8; 3]) -> Option<Cid> {
+        Cid::from_value(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    /// Create a Cid from the low 24 bits of `value`
+    ///
+    /// Returns None if `value` does not fit in 24 bits, or unless the local bit is set and
+    /// the group bit clear.
+    pub const fn from_value(value: u32) -> Option<Cid> {
+        if value >> 24 != 0 || (value >> 16) & 0x03 != 0x02 {
+            return None;
+        }
+        Some(Cid(value))
+    }
+
+    /// Returns the three octets of the Cid
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        let b = self.0.to_be_bytes();
+        [b[1], b[2], b[3]]
+    }
+}
+
+impl MacAddress {
+    /// Returns the first three octets, including the group and local bits
+    pub const fn oui(&self) -> Oui {
+        Oui((self.to_u64() >> 24) as u32)
+    }
+
+    /// Returns the last three octets, assigned by the owner of the OUI
+    pub const fn nic_specific(&self) -> u32 {
+        (self.to_u64() & 0xFF_FFFF) as u32
+    }
+
+    /// Returns the Company ID of a locally administered unicast address, or None
+    pub const fn cid(&self) -> Option<Cid> {
+        Cid::from_value((self.to_u64() >> 24) as u32)
+    }
+}
+
+impl From<MacAddress> for Oui {
+    /// Returns the first 24 bits of `mac`
+    fn from(mac: MacAddress) -> Oui {
+        mac.oui()
+    }
+}
+
+impl From<MacAddress> for OuiMaM {
+    /// Returns the first 28 bits of `mac`
+    fn from(mac: MacAddress) -> OuiMaM {
+        OuiMaM((mac.to_u64() >> 20) as u32)
+    }
+}
+
+impl From<MacAddress> for OuiMaS {
+    /// Returns the first 36 bits of `mac`
+    fn from(mac: MacAddress) -> OuiMaS {
+        OuiMaS(mac.to_u64() >> 12)
+    }
+}
+
+/// Parses exactly `digits` hex digits, optionally split into pairs by one kind of separator
+///
+/// With separators, every group but the last must hold two digits, and the last one or two.
+fn parse_digits(s: &str, digits: usize) -> Result<u64, ParseError> {
+    let mut value = 0;
+    let mut count = 0;
+    let mut separator = None;
+    let mut group_start = 0;
+    for (i, c) in s.char_indices() {
+        if let Some(digit) = c.to_digit(16) {
+            if count < digits {
+                value = (value << 4) | u64::from(digit);
+            }
+            count += 1;
+        } else if matches!(c, '-' | ':' | '.') {
+            match i - group_start {
+                0 => return Err(ParseError::InvalidSeparator(i, c)),
+                2 => {}
+                width => return Err(ParseError::InvalidGroupWidth(group_start, width)),
+            }
+            if *separator.get_or_insert(c) != c {
+                return Err(ParseError::InconsistentSeparators(i, c));
+            }
+            group_start = i + 1;
+        } else {
+            return Err(ParseError::InvalidCharacter(i, c));
+        }
+    }
+    if let Some(c) = separator {
+        match s.len() - group_start {
+            0 => return Err(ParseError::InvalidSeparator(s.len() - 1, c)),
+            1 | 2 => {}
+            width => return Err(ParseError::InvalidGroupWidth(group_start, width)),
+        }
+    }
+    if count != digits {
+        return Err(ParseError::InvalidDigitCount(digits, count));
+    }
+    Ok(value)
+}
+
+/// Renders the low `digits` hex digits of `value` in pairs separated by '-'
+fn write_digits(buf: &mut [u8; 13], value: u64, digits: usize) -> &str {
+    let mut len = 0;
+    for i in 0..digits {
+        if i > 0 && i % 2 == 0 {
+            buf[len] = b'-';
+            len += 1;
+        }
+        let nibble = (value >> (4 * (digits - 1 - i))) & 0x0F;
+        buf[len] = UPPER_HEX_DIGITS[nibble as usize];
+        len += 1;
+    }
+    as_str(&buf[..len])
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessors() {
+        let mac = MacAddress::new([0x70, 0xB3, 0xD5, 0x12, 0x34, 0x56]);
+        assert_eq!(Oui::new([0x70, 0xB3, 0xD5]), mac.oui());
+        assert_eq!([0x70, 0xB3, 0xD5], mac.oui().to_bytes());
+        assert_eq!(0x123456, mac.nic_specific());
+        assert_eq!(None, mac.cid());
+        assert_eq!(0x70B_3D51, OuiMaM::from(mac).value());
+        assert_eq!(0x7_0B3D_5123, OuiMaS::from(mac).value());
+        assert_eq!(mac.oui(), OuiMaM::from(mac).oui());
+        assert_eq!(mac.oui(), OuiMaS::from(mac).oui());
+
+        let local = MacAddress::new([0x0A, 0x1B, 0x2C, 0, 0, 1]);
+        let cid = local.cid().unwrap();
+        assert_eq!([0x0A, 0x1B, 0x2C], cid.to_bytes());
+        assert_eq!(local, cid.with_nic(1));
+        assert_eq!(None, Cid::new([0x00, 0x1B, 0x21]));
+        assert_eq!(None, Cid::new([0x0B, 0x1B, 0x2C]));
+        assert_eq!(None, Cid::from_value(0x10A_1B2C));
+        assert_eq!(None, Oui::from_value(1 << 24));
+        assert_eq!(None, OuiMaM::from_value(1 << 28));
+        assert_eq!(None, OuiMaS::from_value(1 << 36));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let mas = OuiMaS::from_value(0x7_0B3D_5123).unwrap();
+        assert_eq!(
+            MacAddress::new([0x70, 0xB3, 0xD5, 0x12, 0x3F, 0xFF]),
+            mas.with_nic(0xFFFF_FFFF)
+        );
+        assert_eq!("70:b3:d5:12:30:00/36", format!("{:#}", mas.prefix()));
+        assert!(mas.contains(&MacAddress::new([0x70, 0xB3, 0xD5, 0x12, 0x3A, 0xBC])));
+        assert!(!mas.contains(&MacAddress::new([0x70, 0xB3, 0xD5, 0x12, 0x4A, 0xBC])));
+
+        let mam = OuiMaM::from_value(0x70B_3D51).unwrap();
+        assert_eq!(
+            MacAddress::new([0x70, 0xB3, 0xD5, 0x1A, 0xBC, 0xDE]),
+            mam.with_nic(0xABCDE)
+        );
+        assert_eq!("70:b3:d5:10:00:00/28", format!("{:#}", mam.prefix()));
+        let oui = Oui::new([0x00, 0x1B, 0x21]);
+        assert_eq!(1 << 24, oui.prefix().size());
+        assert!(oui.contains(&oui.with_nic(0xFFFFFF)));
+        assert_eq!(24, Oui::BITS);
+    }
+
+    #[test]
+    fn test_parse_display() {
+        use super::ParseError::*;
+
+        let oui = Oui::new([0x00, 0x1B, 0x21]);
+        assert_eq!(Ok(oui), "00-1B-21".parse());
+        assert_eq!(Ok(oui), "00:1b:21".parse());
+        assert_eq!(Ok(oui), "001B21".parse());
+        assert_eq!("00-1B-21", oui.to_string());
+        assert_eq!("Oui(\"00-1B-21\")", format!("{:?}", oui));
+        assert_eq!("  00-1B-21", format!("{:>10}", oui));
+
+        let mam: OuiMaM = "70-B3-D5-1".parse().unwrap();
+        assert_eq!("70-B3-D5-1", mam.to_string());
+        assert_eq!(Ok(mam), "70B3D51".parse());
+        let mas: OuiMaS = "70-B3-D5-12-3".parse().unwrap();
+        assert_eq!("70-B3-D5-12-3", mas.to_string());
+        assert_eq!(
+            "Cid(\"0A-1B-2C\")",
+            format!("{:?}", "0a1b2c".parse::<Cid>().unwrap())
+        );
+
+        assert_eq!(Err(InvalidDigitCount(6, 4)), "00-1B".parse::<Oui>());
+        assert_eq!(Err(InvalidDigitCount(7, 6)), "70-B3-D5".parse::<OuiMaM>());
+        assert_eq!(Err(InvalidDigitCount(6, 0)), "".parse::<Oui>());
+        assert_eq!(Err(InvalidCharacter(4, 'g')), "00-1g-21".parse::<Oui>());
+        assert_eq!(Err(InvalidSeparator(0, '-')), "-001B21".parse::<Oui>());
+        assert_eq!(Err(InvalidSeparator(3, '-')), "00--1B21".parse::<Oui>());
+        assert_eq!(Err(InvalidSeparator(8, '-')), "00-1B-21-".parse::<Oui>());
+        assert_eq!(Err(InvalidGroupWidth(0, 1)), "0-0-1-B-2-1".parse::<Oui>());
+        assert_eq!(Err(InvalidGroupWidth(0, 4)), "001B-21".parse::<Oui>());
+        assert_eq!(Err(InvalidGroupWidth(3, 4)), "00-1B21".parse::<Oui>());
+        assert_eq!(
+            Err(InconsistentSeparators(5, ':')),
+            "00-1B:21".parse::<Oui>()
+        );
+        assert_eq!(Err(InvalidCid(0x001B21)), "00-1B-21".parse::<Cid>());
+        assert_eq!(
+            "Invalid digit count; expecting 6 hex digits, found 4",
+            InvalidDigitCount(6, 4).to_string()
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Bitwise operators and masking on MacAddress values
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::{MacAddress, EUI48LEN};
+
+/// Number of bits in a MacAddress
+const BITS: u32 = 8 * EUI48LEN as u32;
+
+impl MacAddress {
+    /// Returns the address with all but the first `prefix_len` bits cleared
+    ///
+    /// A `prefix_len` above 48 keeps the whole address.
+    ///
+    /// ```
+    /// use eui48::MacAddress;
+    ///
+    /// let mac = MacAddress::new([0x12, 0x34, 0x56, 0xab, 0xcd, 0xef]);
+    /// assert_eq!(MacAddress::new([0x12, 0x34, 0x56, 0, 0, 0]), mac.mask(24));
+    /// ```
+    pub const fn mask(&self, prefix_len: u8) -> MacAddress {
+        let mask = match u64::MAX.checked_shl(BITS - clamp(prefix_len)) {
+            Some(mask) => mask,
+            None => 0,
+        };
+        from_bits(self.to_u64() & mask)
+    }
+
+    /// Returns the number of leading one bits, the prefix length of a mask like ff-ff-ff-00-00-00
+    pub const fn leading_ones(&self) -> u32 {
+        (self.to_u64() << (64 - BITS)).leading_ones()
+    }
+
+    /// Returns the number of leading bits this address shares with `other`
+    pub const fn common_prefix_len(&self, other: &MacAddress) -> u32 {
+        let diff = (self.to_u64() ^ other.to_u64()) << (64 - BITS);
+        if diff == 0 {
+            BITS
+        } else {
+            diff.leading_zeros()
+        }
+    }
+
+    /// Returns the number of bits in which this address differs from `other`
+    pub const fn hamming_distance(&self, other: &MacAddress) -> u32 {
+        (self.to_u64() ^ other.to_u64()).count_ones()
+    }
+}
+
+/// Clamps a prefix length to the number of bits in a MacAddress
+const fn clamp(prefix_len: u8) -> u32 {
+    if prefix_len as u32 > BITS {
+        BITS
+    } else {
+        prefix_len as u32
+    }
+}
+
+/// Create a MacAddress from the low 48 bits of `bits`
+pub(crate) const fn from_bits(bits: u64) -> MacAddress {
+    let bytes = bits.to_be_bytes();
+    MacAddress::new([bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]])
+}
+
+macro_rules! bitwise_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $sym:tt) => {
+        impl $op for MacAddress {
+            type Output = MacAddress;
+
+            fn $method(self, rhs: MacAddress) -> MacAddress {
+                from_bits(self.to_u64() $sym rhs.to_u64())
+            }
+        }
+
+        impl $assign for MacAddress {
+            fn $assign_method(&mut self, rhs: MacAddress) {
+                *self = *self $sym rhs;
+            }
+        }
+    };
+}
+
+bitwise_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bitwise_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bitwise_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for MacAddress {
+    type Output = MacAddress;
+
+    fn not(self) -> MacAddress {
+        from_bits(!self.to_u64())
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let mask = MacAddress::new([0xFF, 0xFF, 0xFF, 0, 0, 0]);
+        assert_eq!(MacAddress::new([0x12, 0x34, 0x56, 0, 0, 0]), mac & mask);
+        assert_eq!(
+            MacAddress::new([0xFF, 0xFF, 0xFF, 0xAB, 0xCD, 0xEF]),
+            mac | mask
+        );
+        assert_eq!(
+            MacAddress::new([0xED, 0xCB, 0xA9, 0xAB, 0xCD, 0xEF]),
+            mac ^ mask
+        );
+        assert_eq!(MacAddress::new([0, 0, 0, 0xFF, 0xFF, 0xFF]), !mask);
+        assert_eq!(MacAddress::broadcast(), !MacAddress::nil());
+
+        let mut m = mac;
+        m &= mask;
+        assert_eq!(mac & mask, m);
+        m |= !mask;
+        assert_eq!(MacAddress::new([0x12, 0x34, 0x56, 0xFF, 0xFF, 0xFF]), m);
+        m ^= m;
+        assert!(m.is_nil());
+    }
+
+    #[test]
+    fn test_mask() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(MacAddress::nil(), mac.mask(0));
+        assert_eq!(
+            MacAddress::new([0x12, 0x34, 0x56, 0xA8, 0, 0]),
+            mac.mask(29)
+        );
+        assert_eq!(mac, mac.mask(48));
+        assert_eq!(mac, mac.mask(255));
+
+        assert_eq!(0, mac.leading_ones());
+        assert_eq!(48, MacAddress::broadcast().leading_ones());
+        assert_eq!(29, MacAddress::broadcast().mask(29).leading_ones());
+
+        let other = MacAddress::new([0x12, 0x34, 0x57, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(23, mac.common_prefix_len(&other));
+        assert_eq!(48, mac.common_prefix_len(&mac));
+        assert_eq!(
+            0,
+            MacAddress::nil().common_prefix_len(&MacAddress::broadcast())
+        );
+
+        assert_eq!(1, mac.hamming_distance(&other));
+        assert_eq!(0, mac.hamming_distance(&mac));
+        assert_eq!(
+            48,
+            MacAddress::nil().hamming_distance(&MacAddress::broadcast())
+        );
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Allocation free rendering of EUI-48 and EUI-64 addresses
+
+use core::fmt;
+
+use super::{Eui64Address, LetterCase, MacAddress, MacAddressFormat};
+
+pub(crate) const LOWER_HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+pub(crate) const UPPER_HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Renders `bytes` in the notation selected by fmt, returning the number of bytes written
+///
+/// `buf` must hold at least `3 * bytes.len() - 1` bytes, the length of the canonical form.
+/// The case only applies to the hex digits, not to the '0x' prefix.
+pub(crate) fn encode(
+    bytes: &[u8],
+    buf: &mut [u8],
+    fmt: MacAddressFormat,
+    case: LetterCase,
+) -> usize {
+    // (prefix, separator, octets per group)
+    let (prefix, separator, group): (&[u8], Option<u8>, usize) = match fmt {
+        MacAddressFormat::Canonical | MacAddressFormat::Irregular => (b"", Some(b'-'), 1),
+        MacAddressFormat::HexString => (b"", Some(b':'), 1),
+        MacAddressFormat::DotNotation => (b"", Some(b'.'), 2),
+        MacAddressFormat::Hexadecimal => (b"0x", None, 1),
+        MacAddressFormat::BareHex => (b"", None, 1),
+        MacAddressFormat::HyphenQuad => (b"", Some(b'-'), 2),
+        MacAddressFormat::ColonHalves => (b"", Some(b':'), bytes.len() / 2),
+        MacAddressFormat::HyphenHalves => (b"", Some(b'-'), bytes.len() / 2),
+        MacAddressFormat::BitReversed => (b"", Some(b':'), 1),
+    };
+    let reversed = fmt == MacAddressFormat::BitReversed;
+    let digits = match case {
+        LetterCase::Lower => LOWER_HEX_DIGITS,
+        LetterCase::Upper => UPPER_HEX_DIGITS,
+    };
+
+    let mut pos = prefix.len();
+    buf[..pos].copy_from_slice(prefix);
+    for (i, &b) in bytes.iter().enumerate() {
+        let b = if reversed { b.reverse_bits() } else { b };
+        if let Some(sep) = separator {
+            if i > 0 && i % group == 0 {
+                buf[pos] = sep;
+                pos += 1;
+            }
+        }
+        buf[pos] = digits[usize::from(b >> 4)];
+        buf[pos + 1] = digits[usize::from(b & 0x0f)];
+        pos += 2;
+    }
+    pos
+}
+
+/// Renders the modified EUI-64 of `mac` as an IPv6 interface identifier like
+/// '1034:56ff:feab:cdef', returning the number of bytes written
+///
+/// `buf` must hold at least 19 bytes.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_interfaceid(mac: &MacAddress, buf: &mut [u8]) -> usize {
+    let iid = mac.to_modified_eui64();
+    let len = encode(
+        iid.as_bytes(),
+        buf,
+        MacAddressFormat::DotNotation,
+        LetterCase::Lower,
+    );
+    for c in buf[..len].iter_mut().filter(|c| **c == b'.') {
+        *c = b':';
+    }
+    len
+}
+
+/// Returns the text rendered by `encode`
+pub(crate) fn as_str(buf: &[u8]) -> &str {
+    core::str::from_utf8(buf).expect("hex digits are ASCII")
+}
+
+/// Writes `bytes` as hex digits for the `LowerHex` and `UpperHex` impls
+///
+/// The alternate flag adds a `0x` prefix, and width, fill and alignment are honoured.
+pub(crate) fn fmt_hex(bytes: &[u8], f: &mut fmt::Formatter, case: LetterCase) -> fmt::Result {
+    let fmt = if f.alternate() {
+        MacAddressFormat::Hexadecimal
+    } else {
+        MacAddressFormat::BareHex
+    };
+    let mut buf = [0; 18];
+    let len = encode(bytes, &mut buf, fmt, case);
+    f.pad(as_str(&buf[..len]))
+}
+
+/// Displays a MacAddress in a fixed notation, without allocating
+///
+/// Returned by `MacAddress::display`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacAddressDisplay {
+    mac: MacAddress,
+    format: MacAddressFormat,
+    case: LetterCase,
+}
+
+impl MacAddressDisplay {
+    pub(crate) fn new(mac: MacAddress, format: MacAddressFormat) -> MacAddressDisplay {
+        MacAddressDisplay {
+            mac,
+            format,
+            case: LetterCase::Lower,
+        }
+    }
+
+    /// Writes the hex digits in the given case, lowercase by default
+    pub fn with_case(mut self, case: LetterCase) -> MacAddressDisplay {
+        self.case = case;
+        self
+    }
+}
+
+impl fmt::Display for MacAddressDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 17];
+        let len = encode(self.mac.as_bytes(), &mut buf, self.format, self.case);
+        f.pad(as_str(&buf[..len]))
+    }
+}
+
+/// Displays an Eui64Address in a fixed notation, without allocating
+///
+/// Returned by `Eui64Address::display`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Eui64AddressDisplay {
+    addr: Eui64Address,
+    format: MacAddressFormat,
+    case: LetterCase,
+}
+
+impl Eui64AddressDisplay {
+    pub(crate) fn new(addr: Eui64Address, format: MacAddressFormat) -> Eui64AddressDisplay {
+        Eui64AddressDisplay {
+            addr,
+            format,
+            case: LetterCase::Lower,
+        }
+    }
+
+    /// Writes the hex digits in the given case, lowercase by default
+    pub fn with_case(mut self, case: LetterCase) -> Eui64AddressDisplay {
+        self.case = case;
+        self
+    }
+}
+
+impl fmt::Display for Eui64AddressDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 23];
+        let len = encode(self.addr.as_bytes(), &mut buf, self.format, self.case);
+        f.pad(as_str(&buf[..len]))
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let eui = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
+        let vectors = [
+            (MacAddressFormat::Canonical, "12-34-56-ab-cd-ef"),
+            (MacAddressFormat::Irregular, "12-34-56-ab-cd-ef"),
+            (MacAddressFormat::HexString, "12:34:56:ab:cd:ef"),
+            (MacAddressFormat::DotNotation, "1234.56ab.cdef"),
+            (MacAddressFormat::Hexadecimal, "0x123456abcdef"),
+            (MacAddressFormat::BareHex, "123456abcdef"),
+            (MacAddressFormat::HyphenQuad, "1234-56ab-cdef"),
+            (MacAddressFormat::ColonHalves, "123456:abcdef"),
+            (MacAddressFormat::HyphenHalves, "123456-abcdef"),
+            (MacAddressFormat::BitReversed, "48:2c:6a:d5:b3:f7"),
+        ];
+        for &(fmt, expected) in vectors.iter() {
+            let mut buf = [0; 17];
+            let len = encode(&eui, &mut buf, fmt, LetterCase::Lower);
+            assert_eq!(expected.as_bytes(), &buf[..len]);
+        }
+
+        let mut buf = [0; 23];
+        let len = encode(
+            &[0xFF; 8],
+            &mut buf,
+            MacAddressFormat::DotNotation,
+            LetterCase::Lower,
+        );
+        assert_eq!(b"ffff.ffff.ffff.ffff", &buf[..len]);
+        let len = encode(
+            &[0xAB; 8],
+            &mut buf,
+            MacAddressFormat::HyphenHalves,
+            LetterCase::Upper,
+        );
+        assert_eq!(b"ABABABAB-ABABABAB", &buf[..len]);
+    }
+
+    #[test]
+    fn test_display() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            "1234.56ab.cdef",
+            format!("{}", mac.display(MacAddressFormat::DotNotation))
+        );
+        assert_eq!(
+            "12:34:56:ab:cd:ef",
+            format!("{}", mac.display(MacAddressFormat::HexString))
+        );
+
+        assert_eq!(
+            "12-34-56-AB-CD-EF",
+            format!(
+                "{}",
+                mac.display(MacAddressFormat::Canonical)
+                    .with_case(LetterCase::Upper)
+            )
+        );
+
+        let addr = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            "0x123456FFFEABCDEF",
+            format!(
+                "{}",
+                addr.display(MacAddressFormat::Hexadecimal)
+                    .with_case(LetterCase::Upper)
+            )
+        );
+        assert_eq!(
+            "0x123456fffeabcdef",
+            format!("{}", addr.display(MacAddressFormat::Hexadecimal))
+        );
+    }
+
+    #[test]
+    fn test_fmt_flags() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!("123456abcdef", format!("{:x}", mac));
+        assert_eq!("123456ABCDEF", format!("{:X}", mac));
+        assert_eq!("0x123456abcdef", format!("{:#x}", mac));
+        assert_eq!("0x123456ABCDEF", format!("{:#X}", mac));
+        assert_eq!("12:34:56:ab:cd:ef", format!("{:#}", mac));
+        assert_eq!("  123456abcdef", format!("{:>14x}", mac));
+        assert_eq!("12:34:56:ab:cd:ef  |", format!("{:<#19}|", mac));
+        assert_eq!("**12:34:56:ab:cd:ef**", format!("{:*^#21}", mac));
+        assert_eq!(
+            "1234.56ab.cdef    |",
+            format!("{:18}|", mac.display(MacAddressFormat::DotNotation))
+        );
+
+        let addr = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+        assert_eq!("123456fffeabcdef", format!("{:x}", addr));
+        assert_eq!("0x123456FFFEABCDEF", format!("{:#X}", addr));
+        assert_eq!("12:34:56:ff:fe:ab:cd:ef", format!("{:#}", addr));
+        assert_eq!("   123456FFFEABCDEF", format!("{:>19X}", addr));
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let mut buf = [0; 17];
+        assert_eq!(
+            "12-34-56-ab-cd-ef",
+            mac.write_to(&mut buf, MacAddressFormat::Canonical)
+        );
+        assert_eq!(
+            "123456abcdef",
+            mac.write_to(&mut buf, MacAddressFormat::BareHex)
+        );
+
+        let addr = Eui64Address::broadcast();
+        let mut buf = [0; 23];
+        assert_eq!(
+            "ff:ff:ff:ff:ff:ff:ff:ff",
+            addr.write_to(&mut buf, MacAddressFormat::HexString)
+        );
+    }
+}
@@ -0,0 +1,723 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Represent and parse IEEE EUI-64 addresses, as used by IEEE 802.15.4 (ZigBee),
+//! IEEE 1394 (FireWire) and IPv6 interface identifiers.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::default::Default;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "rustc-serialize")]
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::display::{as_str, encode, fmt_hex, Eui64AddressDisplay};
+use super::parser::scan_bytes;
+use super::{
+    Eui64, LetterCase, MacAddress, MacAddressFormat, OutOfRangeError, ParseError, EUI64LEN,
+};
+
+/// How an EUI-48 (or MAC-48) address was encapsulated in an EUI-64
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Eui64Encapsulation {
+    /// EUI-48 encapsulation, 'FF-FE' inserted between the OUI and the extension identifier
+    Eui48,
+    /// Deprecated MAC-48 encapsulation, 'FF-FF' inserted between the OUI and the extension
+    /// identifier
+    Mac48,
+}
+
+impl Eui64Encapsulation {
+    /// Returns the two bytes inserted in the middle of the EUI-64
+    pub fn marker(self) -> [u8; 2] {
+        match self {
+            Eui64Encapsulation::Eui48 => [0xFF, 0xFE],
+            Eui64Encapsulation::Mac48 => [0xFF, 0xFF],
+        }
+    }
+}
+
+/// An EUI-64 address
+#[repr(C)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Eui64Address {
+    /// The 64-bit number stored in 8 bytes
+    eui: Eui64,
+}
+
+impl Eui64Address {
+    /// Create a new Eui64Address from `[u8; 8]`.
+    pub const fn new(eui: Eui64) -> Eui64Address {
+        Eui64Address { eui }
+    }
+
+    /// Create a new Eui64Address from a byte slice.
+    ///
+    /// Returns an error (without any description) if the slice doesn't have the proper length.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        if bytes.len() != EUI64LEN {
+            return Err(ParseError::InvalidEui64Length(bytes.len()));
+        }
+        let mut input: [u8; EUI64LEN] = Default::default();
+        input[..EUI64LEN].clone_from_slice(&bytes[..EUI64LEN]);
+        Ok(Self::new(input))
+    }
+
+    /// Returns empty EUI-64 address
+    pub const fn nil() -> Eui64Address {
+        Eui64Address { eui: [0; EUI64LEN] }
+    }
+
+    /// Returns 'ff:ff:ff:ff:ff:ff:ff:ff', the all-ones EUI-64 address
+    pub const fn broadcast() -> Eui64Address {
+        Eui64Address {
+            eui: [0xFF; EUI64LEN],
+        }
+    }
+
+    /// Returns true if the address is '00:00:00:00:00:00:00:00'
+    pub fn is_nil(&self) -> bool {
+        self.eui.iter().all(|&b| b == 0)
+    }
+
+    /// Returns true if the address is 'ff:ff:ff:ff:ff:ff:ff:ff'
+    pub fn is_broadcast(&self) -> bool {
+        self.eui.iter().all(|&b| b == 0xFF)
+    }
+
+    /// Returns true if bit 1 of Y is 0 in address 'xY:xx:xx:xx:xx:xx:xx:xx'
+    pub fn is_unicast(&self) -> bool {
+        self.eui[0] & 1 == 0
+    }
+
+    /// Returns true if bit 1 of Y is 1 in address 'xY:xx:xx:xx:xx:xx:xx:xx'
+    pub fn is_multicast(&self) -> bool {
+        self.eui[0] & 1 == 1
+    }
+
+    /// Returns true if bit 2 of Y is 0 in address 'xY:xx:xx:xx:xx:xx:xx:xx'
+    pub fn is_universal(&self) -> bool {
+        self.eui[0] & 1 << 1 == 0
+    }
+
+    /// Returns true if bit 2 of Y is 1 in address 'xY:xx:xx:xx:xx:xx:xx:xx'
+    pub fn is_local(&self) -> bool {
+        self.eui[0] & 1 << 1 == 2
+    }
+
+    /// Returns a String representation in the format '00-00-00-00-00-00-00-00'
+    #[cfg(feature = "alloc")]
+    pub fn to_canonical(&self) -> String {
+        self.to_string(MacAddressFormat::Canonical)
+    }
+
+    /// Returns a String representation in the format '00:00:00:00:00:00:00:00'
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_string(&self) -> String {
+        self.to_string(MacAddressFormat::HexString)
+    }
+
+    /// Returns a String representation in the format '0000.0000.0000.0000'
+    #[cfg(feature = "alloc")]
+    pub fn to_dot_string(&self) -> String {
+        self.to_string(MacAddressFormat::DotNotation)
+    }
+
+    /// Returns a String representation in the format '0x0000000000000000'
+    #[cfg(feature = "alloc")]
+    pub fn to_hexadecimal(&self) -> String {
+        self.to_string(MacAddressFormat::Hexadecimal)
+    }
+
+    /// Returns a String in the format selected by fmt
+    #[cfg(feature = "alloc")]
+    pub fn to_string(&self, fmt: MacAddressFormat) -> String {
+        let mut buf = [0; 23];
+        String::from(self.write_to(&mut buf, fmt))
+    }
+
+    /// Returns a String in the format selected by fmt, with hex digits in the given case
+    #[cfg(feature = "alloc")]
+    pub fn to_string_with_case(&self, fmt: MacAddressFormat, case: LetterCase) -> String {
+        let mut buf = [0; 23];
+        let len = encode(&self.eui, &mut buf, fmt, case);
+        String::from(as_str(&buf[..len]))
+    }
+
+    /// Returns an adapter that displays the address in the format selected by fmt, without allocating
+    pub fn display(&self, fmt: MacAddressFormat) -> Eui64AddressDisplay {
+        Eui64AddressDisplay::new(*self, fmt)
+    }
+
+    /// Renders the address into `buf` in the format selected by fmt
+    ///
+    /// Returns the rendered part of `buf`; 23 bytes are enough for every notation.
+    pub fn write_to<'a>(&self, buf: &'a mut [u8; 23], fmt: MacAddressFormat) -> &'a str {
+        let len = encode(&self.eui, buf, fmt, LetterCase::Lower);
+        as_str(&buf[..len])
+    }
+
+    /// Parses a String representation from any format supported
+    ///
+    /// Accepts the same notations as `MacAddress::parse_str`, with eight bytes instead of six.
+    pub fn parse_str(s: &str) -> Result<Eui64Address, ParseError> {
+        let mut eui: Eui64 = [0; EUI64LEN];
+
+        match s.len() {
+            15..=23 => {}
+            _ => {
+                return Err(ParseError::InvalidEui64Length(s.len()));
+            }
+        }
+
+        let i = scan_bytes(s, &mut eui);
+        if i != EUI64LEN {
+            return Err(ParseError::InvalidEui64ByteCount(i, eui));
+        }
+
+        Ok(Eui64Address::new(eui))
+    }
+
+    /// Return the internal structure as a slice of bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.eui
+    }
+
+    /// Returns an array in Eui64. Works as an inverse function of new()
+    pub fn to_array(&self) -> Eui64 {
+        self.eui
+    }
+
+    /// Returns the address as a 64-bit integer, first octet most significant
+    pub const fn to_u64(&self) -> u64 {
+        u64::from_be_bytes(self.eui)
+    }
+
+    /// Create an Eui64Address from a 64-bit integer, first octet most significant
+    pub const fn from_u64(value: u64) -> Eui64Address {
+        Eui64Address::new(value.to_be_bytes())
+    }
+
+    /// Returns the octets in transmission order, the same as `to_array`
+    pub const fn to_be_bytes(&self) -> Eui64 {
+        self.eui
+    }
+
+    /// Returns the octets in reverse order, as stored by little-endian hardware registers
+    pub const fn to_le_bytes(&self) -> Eui64 {
+        u64::from_be_bytes(self.eui).to_le_bytes()
+    }
+
+    /// Create an Eui64Address from octets in transmission order, the same as `new`
+    pub const fn from_be_bytes(eui: Eui64) -> Eui64Address {
+        Eui64Address::new(eui)
+    }
+
+    /// Create an Eui64Address from octets in reverse order, the inverse of `to_le_bytes`
+    pub const fn from_le_bytes(eui: Eui64) -> Eui64Address {
+        Eui64Address::new(u64::from_le_bytes(eui).to_be_bytes())
+    }
+
+    /// Returns the address with the universal/local bit inverted
+    ///
+    /// Converts between an EUI-64 and the modified EUI-64 used for IPv6 interface
+    /// identifiers (RFC 4291, Appendix A). Applying it twice returns the original address.
+    pub fn to_modified(&self) -> Eui64Address {
+        let mut eui = self.eui;
+        eui[0] ^= 0x02;
+        Eui64Address::new(eui)
+    }
+
+    /// Returns the encapsulation found in bytes 3 and 4, if the address encapsulates a MAC
+    pub fn encapsulation(&self) -> Option<Eui64Encapsulation> {
+        match [self.eui[3], self.eui[4]] {
+            [0xFF, 0xFE] => Some(Eui64Encapsulation::Eui48),
+            [0xFF, 0xFF] => Some(Eui64Encapsulation::Mac48),
+            _ => None,
+        }
+    }
+
+    /// Recovers the encapsulated MacAddress and the encapsulation that was used
+    ///
+    /// Returns None if the address does not carry an 'FF-FE' or 'FF-FF' marker.
+    pub fn to_mac_address(&self) -> Option<(MacAddress, Eui64Encapsulation)> {
+        let encapsulation = self.encapsulation()?;
+        let mac = MacAddress::new([
+            self.eui[0],
+            self.eui[1],
+            self.eui[2],
+            self.eui[5],
+            self.eui[6],
+            self.eui[7],
+        ]);
+        Some((mac, encapsulation))
+    }
+}
+
+impl MacAddress {
+    /// Returns the EUI-64 encapsulating this address using the given marker
+    pub fn to_eui64_with(&self, encapsulation: Eui64Encapsulation) -> Eui64Address {
+        let marker = encapsulation.marker();
+        Eui64Address::new([
+            self.eui[0],
+            self.eui[1],
+            self.eui[2],
+            marker[0],
+            marker[1],
+            self.eui[3],
+            self.eui[4],
+            self.eui[5],
+        ])
+    }
+
+    /// Returns the EUI-64 'xx-xx-xx-FF-FE-xx-xx-xx' encapsulating this address
+    pub fn to_eui64(&self) -> Eui64Address {
+        self.to_eui64_with(Eui64Encapsulation::Eui48)
+    }
+
+    /// Returns the EUI-64 'xx-xx-xx-FF-FF-xx-xx-xx' using the deprecated MAC-48 encapsulation
+    pub fn to_eui64_mac48(&self) -> Eui64Address {
+        self.to_eui64_with(Eui64Encapsulation::Mac48)
+    }
+
+    /// Returns the modified EUI-64 used as an IPv6 interface identifier
+    ///
+    /// This is the 'FF-FE' encapsulation with the universal/local bit inverted.
+    pub fn to_modified_eui64(&self) -> Eui64Address {
+        self.to_eui64().to_modified()
+    }
+
+    /// Recovers a MacAddress from an EUI-64, see `Eui64Address::to_mac_address`
+    pub fn from_eui64(eui: &Eui64Address) -> Option<(MacAddress, Eui64Encapsulation)> {
+        eui.to_mac_address()
+    }
+
+    /// Recovers a MacAddress from a modified EUI-64 (IPv6 interface identifier)
+    pub fn from_modified_eui64(eui: &Eui64Address) -> Option<(MacAddress, Eui64Encapsulation)> {
+        eui.to_modified().to_mac_address()
+    }
+}
+
+impl From<MacAddress> for Eui64Address {
+    /// Encapsulate a MacAddress using the 'FF-FE' EUI-48 encapsulation
+    fn from(mac: MacAddress) -> Eui64Address {
+        mac.to_eui64()
+    }
+}
+
+impl FromStr for Eui64Address {
+    type Err = ParseError;
+    /// Create an Eui64Address from String
+    fn from_str(us: &str) -> Result<Eui64Address, ParseError> {
+        Eui64Address::parse_str(us)
+    }
+}
+
+impl From<Eui64Address> for u64 {
+    /// The address as a 64-bit integer, see `Eui64Address::to_u64`
+    fn from(addr: Eui64Address) -> u64 {
+        addr.to_u64()
+    }
+}
+
+impl From<Eui64Address> for u128 {
+    /// The address as a 64-bit integer, see `Eui64Address::to_u64`
+    fn from(addr: Eui64Address) -> u128 {
+        u128::from(addr.to_u64())
+    }
+}
+
+impl From<u64> for Eui64Address {
+    /// Create an Eui64Address from a 64-bit integer, see `Eui64Address::from_u64`
+    fn from(value: u64) -> Eui64Address {
+        Eui64Address::from_u64(value)
+    }
+}
+
+impl TryFrom<u128> for Eui64Address {
+    type Error = OutOfRangeError;
+    /// Create an Eui64Address from a 64-bit integer, rejecting larger values
+    fn try_from(value: u128) -> Result<Eui64Address, OutOfRangeError> {
+        u64::try_from(value)
+            .map(Eui64Address::from_u64)
+            .map_err(|_| OutOfRangeError(value))
+    }
+}
+
+impl Default for Eui64Address {
+    /// Create a Default Eui64Address (00-00-00-00-00-00-00-00)
+    fn default() -> Eui64Address {
+        Eui64Address::nil()
+    }
+}
+
+impl fmt::Debug for Eui64Address {
+    /// Debug format for Eui64Address is HexString notation
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 23];
+        write!(
+            f,
+            "Eui64Address(\"{}\")",
+            self.write_to(&mut buf, MacAddressFormat::HexString)
+        )
+    }
+}
+
+impl fmt::Display for Eui64Address {
+    /// Display format follows MacAddress, canonical format by default and HexString for `{:#}`
+    ///
+    /// A BitReversed default is written as HexString, so the output can be parsed back.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fmt = if f.alternate() {
+            MacAddressFormat::HexString
+        } else {
+            MacAddress::get_display_format().parseable()
+        };
+        let mut buf = [0; 23];
+        f.pad(self.write_to(&mut buf, fmt))
+    }
+}
+
+impl fmt::LowerHex for Eui64Address {
+    /// Bare lower case hex digits, with a '0x' prefix in alternate form
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_hex(&self.eui, f, LetterCase::Lower)
+    }
+}
+
+impl fmt::UpperHex for Eui64Address {
+    /// Bare upper case hex digits, with a '0x' prefix in alternate form
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_hex(&self.eui, f, LetterCase::Upper)
+    }
+}
+
+#[cfg(feature = "rustc-serialize")]
+impl Encodable for Eui64Address {
+    /// Encode an Eui64Address using the default format
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        let disp_fmt = MacAddress::get_display_format().parseable();
+        e.emit_str(&self.to_string(disp_fmt))
+    }
+}
+
+#[cfg(feature = "rustc-serialize")]
+impl Decodable for Eui64Address {
+    /// Decode an Eui64Address from a string in canonical form
+    fn decode<D: Decoder>(d: &mut D) -> Result<Eui64Address, D::Error> {
+        let string = d.read_str()?;
+        string.parse().map_err(|err| d.error(&format!("{}", err)))
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "serde_bytes")))]
+impl Serialize for Eui64Address {
+    /// Serialize an Eui64Address in the default format using the serde crate
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde_bytes")]
+impl Serialize for Eui64Address {
+    /// Serialize an Eui64Address as raw bytes using the serde crate
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "serde_bytes")))]
+impl<'de> Deserialize<'de> for Eui64Address {
+    /// Deserialize an Eui64Address from canonical form using the serde crate
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Eui64AddressVisitor;
+        impl<'de> de::Visitor<'de> for Eui64AddressVisitor {
+            type Value = Eui64Address;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a string representation of an EUI-64 address")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_str(Eui64AddressVisitor)
+    }
+}
+
+#[cfg(feature = "serde_bytes")]
+impl<'de> Deserialize<'de> for Eui64Address {
+    /// Deserialize an Eui64Address from raw bytes using the serde crate
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Eui64AddressVisitor;
+        impl<'de> de::Visitor<'de> for Eui64AddressVisitor {
+            type Value = Eui64Address;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "8-element byte array")
+            }
+
+            fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Eui64Address::from_bytes(value).map_err(|_| E::invalid_length(value.len(), &self))
+            }
+        }
+        deserializer.deserialize_bytes(Eui64AddressVisitor)
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::{Eui64, Eui64Address, Eui64Encapsulation, MacAddress, OutOfRangeError, ParseError};
+
+    #[test]
+    fn test_new() {
+        let eui: Eui64 = [0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF];
+        let addr = Eui64Address::new(eui);
+
+        assert_eq!(addr.eui, eui);
+        assert_eq!(eui, addr.to_array());
+        assert_eq!(&eui[..], addr.as_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_from_bytes() {
+        assert_eq!(
+            "12:34:56:ff:fe:ab:cd:ef",
+            Eui64Address::from_bytes(&[0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF])
+                .unwrap()
+                .to_hex_string()
+        );
+        assert_eq!(
+            Eui64Address::from_bytes(&[0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]),
+            Err(ParseError::InvalidEui64Length(6))
+        );
+    }
+
+    #[test]
+    fn test_nil_broadcast_default() {
+        assert!(Eui64Address::nil().is_nil());
+        assert!(Eui64Address::default().is_nil());
+        assert!(!Eui64Address::broadcast().is_nil());
+        assert!(Eui64Address::broadcast().is_broadcast());
+        assert!(!Eui64Address::nil().is_broadcast());
+    }
+
+    #[test]
+    fn test_classification() {
+        let addr = Eui64Address::parse_str("00:12:4B:00:01:02:03:04").unwrap();
+        assert!(addr.is_unicast());
+        assert!(!addr.is_multicast());
+        assert!(addr.is_universal());
+        assert!(!addr.is_local());
+
+        let addr = Eui64Address::parse_str("03:12:4B:00:01:02:03:04").unwrap();
+        assert!(!addr.is_unicast());
+        assert!(addr.is_multicast());
+        assert!(!addr.is_universal());
+        assert!(addr.is_local());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_to_string() {
+        use super::MacAddressFormat;
+
+        let eui: Eui64 = [0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF];
+        let addr = Eui64Address::new(eui);
+        assert_eq!(
+            "12-34-56-ff-fe-ab-cd-ef",
+            addr.to_string(MacAddressFormat::Canonical)
+        );
+        assert_eq!(
+            "12:34:56:ff:fe:ab:cd:ef",
+            addr.to_string(MacAddressFormat::HexString)
+        );
+        assert_eq!(
+            "1234.56ff.feab.cdef",
+            addr.to_string(MacAddressFormat::DotNotation)
+        );
+        assert_eq!(
+            "0x123456fffeabcdef",
+            addr.to_string(MacAddressFormat::Hexadecimal)
+        );
+    }
+
+    #[test]
+    fn test_parse_str() {
+        use super::ParseError::*;
+
+        let expected = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            Ok(expected),
+            Eui64Address::parse_str("12-34-56-FF-FE-AB-CD-EF")
+        );
+        assert_eq!(
+            Ok(expected),
+            Eui64Address::parse_str("12:34:56:ff:fe:ab:cd:ef")
+        );
+        assert_eq!(Ok(expected), Eui64Address::parse_str("1234.56ff.feab.cdef"));
+        assert_eq!(Ok(expected), Eui64Address::parse_str("1234-56ff-feab-cdef"));
+        assert_eq!(Ok(expected), Eui64Address::parse_str("123456ff:feabcdef"));
+        assert_eq!(Ok(expected), Eui64Address::parse_str("0x123456FFFEABCDEF"));
+        assert_eq!(Ok(expected), Eui64Address::parse_str("123456fffeabcdef"));
+        assert_eq!(Ok(expected), "12:34:56:ff:fe:ab:cd:ef".parse());
+
+        assert_eq!(
+            Eui64Address::parse_str("12:34:56:ab:cd"),
+            Err(InvalidEui64Length(14))
+        );
+        assert_eq!(
+            Eui64Address::parse_str("12:34:56:ab:cd:ef"),
+            Err(InvalidEui64ByteCount(
+                6,
+                [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF, 0, 0]
+            ))
+        );
+        assert_eq!(
+            Eui64Address::parse_str("12:34:56:ab:cd:ef:01:"),
+            Err(InvalidEui64ByteCount(
+                7,
+                [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF, 0x01, 0]
+            ))
+        );
+        assert_eq!(
+            Eui64Address::parse_str("123456fffeabcdef01"),
+            Err(InvalidEui64ByteCount(
+                9,
+                [0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_fmt() {
+        let addr = Eui64Address::parse_str("12:34:56:ff:fe:ab:cd:ef").unwrap();
+        assert_eq!(
+            "Eui64Address(\"12:34:56:ff:fe:ab:cd:ef\")",
+            format!("{:?}", addr)
+        );
+        if cfg!(feature = "disp_hexstring") {
+            assert_eq!("12:34:56:ff:fe:ab:cd:ef", format!("{}", addr));
+        } else {
+            assert_eq!("12-34-56-ff-fe-ab-cd-ef", format!("{}", addr));
+        }
+    }
+
+    #[test]
+    fn test_parseerror_fmt() {
+        assert_eq!(
+            "Invalid length; expecting 15 to 23 chars, found 2",
+            ParseError::InvalidEui64Length(2).to_string()
+        );
+        assert_eq!(
+            "Invalid byte count; Matched `9` bytes ([0, 0, 0, 0, 0, 0, 0, 0])",
+            ParseError::InvalidEui64ByteCount(9, [0; 8]).to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rustc-serialize")]
+    fn test_serialize_roundtrip() {
+        use rustc_serialize::json;
+
+        let a1 = Eui64Address::parse_str("12:34:56:ff:fe:ab:cd:ef").unwrap();
+        let s = json::encode(&a1).unwrap();
+        let a2 = json::decode(&s).unwrap();
+        assert_eq!(a1, a2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde_json", not(feature = "serde_bytes")))]
+    fn test_serde_json_roundtrip() {
+        let a1 = Eui64Address::parse_str("12:34:56:ff:fe:ab:cd:ef").unwrap();
+        let s = serde_json::to_string(&a1).unwrap();
+        let a2: Eui64Address = serde_json::from_str(&s).unwrap();
+        assert_eq!(a1, a2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde_bytes")]
+    fn test_serde_bytes_serialization_roundtrip() {
+        let a1 = Eui64Address::parse_str("12:34:56:ff:fe:ab:cd:ef").unwrap();
+        let mut buffer = Vec::new();
+        bincode::serialize_into(&mut buffer, &a1).unwrap();
+        let a2: Eui64Address = bincode::deserialize_from(&*buffer).unwrap();
+        assert_eq!(a1, a2);
+    }
+
+    #[test]
+    fn test_integers() {
+        use std::convert::TryFrom;
+
+        let addr = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(0x1234_56FF_FEAB_CDEF, addr.to_u64());
+        assert_eq!(0x1234_56FF_FEAB_CDEF_u64, u64::from(addr));
+        assert_eq!(0x1234_56FF_FEAB_CDEF_u128, u128::from(addr));
+        assert_eq!(addr, Eui64Address::from_u64(0x1234_56FF_FEAB_CDEF));
+        assert_eq!(addr, Eui64Address::from(0x1234_56FF_FEAB_CDEF_u64));
+        assert_eq!(Ok(addr), Eui64Address::try_from(0x1234_56FF_FEAB_CDEF_u128));
+        assert_eq!(
+            Err(OutOfRangeError(1 << 64)),
+            Eui64Address::try_from(1_u128 << 64)
+        );
+
+        assert_eq!(addr.to_array(), addr.to_be_bytes());
+        assert_eq!(
+            [0xEF, 0xCD, 0xAB, 0xFE, 0xFF, 0x56, 0x34, 0x12],
+            addr.to_le_bytes()
+        );
+        assert_eq!(addr, Eui64Address::from_le_bytes(addr.to_le_bytes()));
+        assert_eq!(addr, Eui64Address::from_be_bytes(addr.to_be_bytes()));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_mac_to_eui64() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!("12:34:56:ff:fe:ab:cd:ef", mac.to_eui64().to_hex_string());
+        assert_eq!(
+            "12:34:56:ff:ff:ab:cd:ef",
+            mac.to_eui64_mac48().to_hex_string()
+        );
+        assert_eq!(
+            "10:34:56:ff:fe:ab:cd:ef",
+            mac.to_modified_eui64().to_hex_string()
+        );
+        assert_eq!(mac.to_eui64(), Eui64Address::from(mac));
+        assert_eq!(mac.to_eui64(), mac.to_modified_eui64().to_modified());
+    }
+
+    #[test]
+    fn test_eui64_to_mac() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            Some((mac, Eui64Encapsulation::Eui48)),
+            mac.to_eui64().to_mac_address()
+        );
+        assert_eq!(
+            Some((mac, Eui64Encapsulation::Mac48)),
+            MacAddress::from_eui64(&mac.to_eui64_mac48())
+        );
+        assert_eq!(
+            Some((mac, Eui64Encapsulation::Eui48)),
+            MacAddress::from_modified_eui64(&mac.to_modified_eui64())
+        );
+
+        let native = Eui64Address::parse_str("00:12:4b:00:01:02:03:04").unwrap();
+        assert_eq!(None, native.encapsulation());
+        assert_eq!(None, native.to_mac_address());
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Notations pinned at the type level, for use with `MacAddressWith`
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{MacAddress, MacAddressFormat, ParseError};
+
+/// A type naming one notation of `MacAddressFormat`
+pub trait FormatMarker {
+    /// The notation used to display and serialize a `MacAddressWith<Self>`
+    const FORMAT: MacAddressFormat;
+}
+
+macro_rules! format_marker {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name;
+
+        impl FormatMarker for $name {
+            const FORMAT: MacAddressFormat = MacAddressFormat::$name;
+        }
+    };
+}
+
+format_marker!(
+    /// Marker for '00-00-00-00-00-00'
+    Canonical
+);
+format_marker!(
+    /// Marker for '00:00:00:00:00:00'
+    HexString
+);
+format_marker!(
+    /// Marker for '0000.0000.0000'
+    DotNotation
+);
+format_marker!(
+    /// Marker for '0x000000000000'
+    Hexadecimal
+);
+format_marker!(
+    /// Marker for '000000000000'
+    BareHex
+);
+format_marker!(
+    /// Marker for '0000-0000-0000'
+    HyphenQuad
+);
+format_marker!(
+    /// Marker for '000000:000000'
+    ColonHalves
+);
+format_marker!(
+    /// Marker for '000000-000000'
+    HyphenHalves
+);
+format_marker!(
+    /// Marker for the bit-reversed '00:00:00:00:00:00'
+    BitReversed
+);
+
+/// A MacAddress that always displays and serializes in the notation named by `F`
+///
+/// Unlike `MacAddress`, the notation does not follow `MacAddress::set_default_format`,
+/// so crates sharing a process can each pick their own.
+///
+/// ```
+/// use eui48::formats::DotNotation;
+/// use eui48::{MacAddress, MacAddressWith};
+///
+/// let mac = MacAddress::new([0x12, 0x34, 0x56, 0xab, 0xcd, 0xef]);
+/// let pinned: MacAddressWith<DotNotation> = mac.into();
+/// assert_eq!("1234.56ab.cdef", pinned.to_string());
+/// ```
+#[repr(transparent)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MacAddressWith<F> {
+    mac: MacAddress,
+    format: PhantomData<F>,
+}
+
+impl<F: FormatMarker> MacAddressWith<F> {
+    /// Wraps `mac`, pinning its notation to `F`
+    pub const fn new(mac: MacAddress) -> MacAddressWith<F> {
+        MacAddressWith {
+            mac,
+            format: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped MacAddress
+    pub const fn into_inner(self) -> MacAddress {
+        self.mac
+    }
+}
+
+impl<F: FormatMarker> Default for MacAddressWith<F> {
+    fn default() -> MacAddressWith<F> {
+        MacAddressWith::new(MacAddress::nil())
+    }
+}
+
+impl<F: FormatMarker> From<MacAddress> for MacAddressWith<F> {
+    fn from(mac: MacAddress) -> MacAddressWith<F> {
+        MacAddressWith::new(mac)
+    }
+}
+
+impl<F: FormatMarker> From<MacAddressWith<F>> for MacAddress {
+    fn from(mac: MacAddressWith<F>) -> MacAddress {
+        mac.mac
+    }
+}
+
+impl<F: FormatMarker> AsRef<MacAddress> for MacAddressWith<F> {
+    fn as_ref(&self) -> &MacAddress {
+        &self.mac
+    }
+}
+
+impl<F: FormatMarker> FromStr for MacAddressWith<F> {
+    type Err = ParseError;
+    /// Parses any notation accepted by `MacAddress::parse_str`, reversing the bits of each
+    /// octet if `F` is BitReversed
+    fn from_str(s: &str) -> Result<MacAddressWith<F>, ParseError> {
+        let mac = MacAddress::parse_str(s)?;
+        Ok(MacAddressWith::new(match F::FORMAT {
+            MacAddressFormat::BitReversed => mac.bit_reversed(),
+            _ => mac,
+        }))
+    }
+}
+
+impl<F: FormatMarker> fmt::Debug for MacAddressWith<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.mac, f)
+    }
+}
+
+impl<F: FormatMarker> fmt::Display for MacAddressWith<F> {
+    /// Display format is `F::FORMAT`; width, fill and alignment are honoured
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.mac.display(F::FORMAT), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<F: FormatMarker> Serialize for MacAddressWith<F> {
+    /// Serialize a MacAddressWith as a string in the `F::FORMAT` notation, even with
+    /// the `serde_bytes` feature
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: FormatMarker> Deserialize<'de> for MacAddressWith<F> {
+    /// Deserialize a MacAddressWith from a string in any notation
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MacAddressWithVisitor<F>(PhantomData<F>);
+        impl<'de, F: FormatMarker> de::Visitor<'de> for MacAddressWithVisitor<F> {
+            type Value = MacAddressWith<F>;
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a string representation of a MAC address")
+            }
+        }
+        deserializer.deserialize_str(MacAddressWithVisitor(PhantomData))
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            "12-34-56-ab-cd-ef",
+            MacAddressWith::<Canonical>::new(mac).to_string()
+        );
+        assert_eq!(
+            "12:34:56:ab:cd:ef",
+            MacAddressWith::<HexString>::new(mac).to_string()
+        );
+        assert_eq!(
+            "1234.56ab.cdef",
+            MacAddressWith::<DotNotation>::new(mac).to_string()
+        );
+        assert_eq!(
+            "0x123456abcdef",
+            MacAddressWith::<Hexadecimal>::new(mac).to_string()
+        );
+        assert_eq!(
+            "  123456abcdef",
+            format!("{:>14}", MacAddressWith::<BareHex>::new(mac))
+        );
+        assert_eq!(
+            "MacAddress(\"12:34:56:ab:cd:ef\")",
+            format!("{:?}", MacAddressWith::<BareHex>::new(mac))
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let pinned: MacAddressWith<DotNotation> = "12:34:56:ab:cd:ef".parse().unwrap();
+        assert_eq!(mac, pinned.into_inner());
+        assert_eq!(mac, MacAddress::from(pinned));
+        assert_eq!(&mac, pinned.as_ref());
+        assert_eq!(pinned, MacAddressWith::from(mac));
+        assert_eq!(
+            MacAddress::nil(),
+            MacAddressWith::<BareHex>::default().into()
+        );
+        let reversed: MacAddressWith<BitReversed> = "48:2c:6a:d5:b3:f7".parse().unwrap();
+        assert_eq!(mac, reversed.into_inner());
+        assert_eq!("48:2c:6a:d5:b3:f7", reversed.to_string());
+        assert_eq!(
+            Err(ParseError::InvalidLength(2)),
+            "12".parse::<MacAddressWith<BareHex>>()
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn test_serde_json() {
+        use serde_json;
+
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let pinned = MacAddressWith::<DotNotation>::new(mac);
+        let json = serde_json::to_string(&pinned).unwrap();
+        assert_eq!("\"1234.56ab.cdef\"", json);
+        let back: MacAddressWith<DotNotation> = serde_json::from_str(&json).unwrap();
+        assert_eq!(pinned, back);
+        let other: MacAddressWith<DotNotation> =
+            serde_json::from_str("\"12:34:56:ab:cd:ef\"").unwrap();
+        assert_eq!(pinned, other);
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Custom layouts for MacAddress output and input
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use super::display::{as_str, LOWER_HEX_DIGITS, UPPER_HEX_DIGITS};
+use super::{Eui48, LetterCase, MacAddress, MacAddressFormat, ParseError, EUI48LEN};
+
+/// Number of hex digits in a MacAddress
+const NIBBLES: usize = 2 * EUI48LEN;
+
+/// Longest prefix accepted by `MacFormatter::prefix`, in bytes
+const MAX_PREFIX: usize = 16;
+
+/// Longest output of a MacFormatter: the prefix, the digits and a 4 byte separator between each
+const MAX_LEN: usize = MAX_PREFIX + NIBBLES + 4 * (NIBBLES - 1);
+
+/// A custom layout for writing and reading MAC addresses
+///
+/// The twelve hex digits are split in groups of `group_size` digits, counted from the left,
+/// with `separator` between the groups and `prefix` in front. The default layout is the
+/// Canonical notation, and every `MacAddressFormat` converts into a MacFormatter writing the
+/// same string as `MacAddress::to_string`.
+///
+/// ```
+/// use eui48::{LetterCase, MacAddress, MacFormatter};
+///
+/// let mac = MacAddress::new([0x12, 0x34, 0x56, 0xab, 0xcd, 0xef]);
+/// let spaced = MacFormatter::new().separator(' ').case(LetterCase::Upper);
+/// assert_eq!("12 34 56 AB CD EF", spaced.format(&mac));
+/// assert_eq!(Ok(mac), spaced.parse("12 34 56 AB CD EF"));
+///
+/// let halves = MacFormatter::new().group_size(6).separator(':');
+/// assert_eq!("123456:abcdef", halves.display(mac).to_string());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacFormatter {
+    /// Hex digits per group
+    group_size: usize,
+    /// Written between groups
+    separator: Option<char>,
+    /// Case of the hex digits a-f
+    case: LetterCase,
+    /// Written before the first group
+    prefix: &'static str,
+    /// Reverse the bits of each octet
+    bit_reversed: bool,
+}
+
+impl MacFormatter {
+    /// Create a formatter for the Canonical notation, '12-34-56-ab-cd-ef'
+    pub const fn new() -> MacFormatter {
+        MacFormatter {
+            group_size: 2,
+            separator: Some('-'),
+            case: LetterCase::Lower,
+            prefix: "",
+            bit_reversed: false,
+        }
+    }
+
+    /// Split the hex digits in groups of `nibbles` digits; the last group is shorter if
+    /// `nibbles` does not divide 12
+    ///
+    /// Panics if `nibbles` is 0.
+    pub const fn group_size(mut self, nibbles: usize) -> MacFormatter {
+        assert!(nibbles > 0, "group size must be at least one hex digit");
+        self.group_size = nibbles;
+        self
+    }
+
+    /// Write `separator` between groups
+    pub const fn separator(mut self, separator: char) -> MacFormatter {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Write the groups without separators
+    pub const fn no_separator(mut self) -> MacFormatter {
+        self.separator = None;
+        self
+    }
+
+    /// Write the hex digits a-f in the given case
+    pub const fn case(mut self, case: LetterCase) -> MacFormatter {
+        self.case = case;
+        self
+    }
+
+    /// Write `prefix` before the first group, such as "0x"
+    ///
+    /// Panics if `prefix` is longer than 16 bytes.
+    pub const fn prefix(mut self, prefix: &'static str) -> MacFormatter {
+        assert!(
+            prefix.len() <= MAX_PREFIX,
+            "prefix must be at most 16 bytes"
+        );
+        self.prefix = prefix;
+        self
+    }
+
+    /// Write and read the octets in bit-reversed order, as `MacAddress::bit_reversed`
+    pub const fn bit_reversed(mut self, reversed: bool) -> MacFormatter {
+        self.bit_reversed = reversed;
+        self
+    }
+
+    /// Returns a String with the address in this layout
+    #[cfg(feature = "alloc")]
+    pub fn format(&self, mac: &MacAddress) -> String {
+        let mut s = String::with_capacity(self.prefix.len() + 2 * NIBBLES);
+        self.write(&mut s, mac).unwrap();
+        s
+    }
+
+    /// Returns an adapter that displays the address in this layout, without allocating
+    pub fn display(&self, mac: MacAddress) -> MacFormatterDisplay {
+        MacFormatterDisplay {
+            formatter: *self,
+            mac,
+        }
+    }
+
+    /// Writes the address in this layout to `w`
+    pub fn write<W: Write>(&self, w: &mut W, mac: &MacAddress) -> fmt::Result {
+        let digits = match self.case {
+            LetterCase::Lower => LOWER_HEX_DIGITS,
+            LetterCase::Upper => UPPER_HEX_DIGITS,
+        };
+        let mac = if self.bit_reversed {
+            mac.bit_reversed()
+        } else {
+            *mac
+        };
+        w.write_str(self.prefix)?;
+        for (i, &b) in mac.as_bytes().iter().enumerate() {
+            for (j, nibble) in [b >> 4, b & 0x0f].iter().enumerate() {
+                let k = 2 * i + j;
+                if let Some(sep) = self.separator {
+                    if k > 0 && k % self.group_size == 0 {
+                        w.write_char(sep)?;
+                    }
+                }
+                w.write_char(char::from(digits[usize::from(*nibble)]))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a MacAddress written in exactly this layout
+    ///
+    /// The prefix, the separators and the case of the hex digits must match; offsets in the
+    /// returned errors are byte offsets into `s`.
+    pub fn parse(&self, s: &str) -> Result<MacAddress, ParseError> {
+        if !s.starts_with(self.prefix) {
+            return Err(
+                match s
+                    .char_indices()
+                    .zip(self.prefix.chars())
+                    .find(|&((_, a), b)| a != b)
+                {
+                    Some(((pos, c), _)) => ParseError::InvalidCharacter(pos, c),
+                    None => ParseError::InvalidLength(s.len()),
+                },
+            );
+        }
+
+        let mut eui: Eui48 = [0; EUI48LEN];
+        let mut chars = s
+            .char_indices()
+            .skip(self.prefix.chars().count())
+            .peekable();
+        let mut group_start = self.prefix.len();
+        for k in 0..NIBBLES {
+            let at_boundary = k > 0 && k % self.group_size == 0;
+            let in_group = k % self.group_size;
+            if let (Some(sep), true) = (self.separator, at_boundary) {
+                match chars.next() {
+                    None => return Err(missing_digits(k, eui)),
+                    Some((_, c)) if c == sep => {}
+                    Some((_, c)) if c.is_ascii_hexdigit() => {
+                        let width = run_length(&s[group_start..]);
+                        return Err(ParseError::InvalidGroupWidth(group_start, width));
+                    }
+                    Some((pos, c)) => return Err(ParseError::InvalidSeparator(pos, c)),
+                }
+                group_start = chars.peek().map_or(s.len(), |&(pos, _)| pos);
+            }
+            match chars.next() {
+                None if in_group == 0 => return Err(missing_digits(k, eui)),
+                None => return Err(ParseError::InvalidGroupWidth(group_start, in_group)),
+                Some((pos, c)) => match self.hex_value(c) {
+                    Some(v) => eui[k / 2] = eui[k / 2] << 4 | v,
+                    None if Some(c) == self.separator && in_group > 0 => {
+                        return Err(ParseError::InvalidGroupWidth(group_start, in_group))
+                    }
+                    None => return Err(ParseError::InvalidCharacter(pos, c)),
+                },
+            }
+        }
+        match chars.next() {
+            Some((pos, _)) => Err(ParseError::TrailingCharacters(pos)),
+            None if self.bit_reversed => Ok(MacAddress::from_bit_reversed(eui)),
+            None => Ok(MacAddress::new(eui)),
+        }
+    }
+
+    /// Returns the value of a hex digit written in the configured case
+    fn hex_value(&self, c: char) -> Option<u8> {
+        let v = c.to_digit(16)? as u8;
+        match self.case {
+            LetterCase::Lower if c.is_ascii_uppercase() => None,
+            LetterCase::Upper if c.is_ascii_lowercase() => None,
+            _ => Some(v),
+        }
+    }
+}
+
+/// Returns the error for input ending after `digits` hex digits
+///
+/// Input ending within an octet, possible with an odd group size, reports the digit count.
+fn missing_digits(digits: usize, eui: Eui48) -> ParseError {
+    if digits % 2 == 0 {
+        ParseError::InvalidByteCount(digits / 2, eui)
+    } else {
+        ParseError::InvalidDigitCount(NIBBLES, digits)
+    }
+}
+
+/// Returns the number of leading hex digits in `s`
+fn run_length(s: &str) -> usize {
+    s.bytes().take_while(u8::is_ascii_hexdigit).count()
+}
+
+impl Default for MacFormatter {
+    /// Create a formatter for the Canonical notation
+    fn default() -> MacFormatter {
+        MacFormatter::new()
+    }
+}
+
+impl From<MacAddressFormat> for MacFormatter {
+    /// Create a formatter writing the notation in lowercase, as `MacAddress::to_string` does
+    fn from(fmt: MacAddressFormat) -> MacFormatter {
+        let formatter = MacFormatter::new();
+        match fmt {
+            MacAddressFormat::Canonical | MacAddressFormat::Irregular => formatter,
+            MacAddressFormat::HexString => formatter.separator(':'),
+            MacAddressFormat::DotNotation => formatter.group_size(4).separator('.'),
+            MacAddressFormat::Hexadecimal => {
+                formatter.group_size(NIBBLES).no_separator().prefix("0x")
+            }
+            MacAddressFormat::BareHex => formatter.group_size(NIBBLES).no_separator(),
+            MacAddressFormat::HyphenQuad => formatter.group_size(4),
+            MacAddressFormat::ColonHalves => formatter.group_size(6).separator(':'),
+            MacAddressFormat::HyphenHalves => formatter.group_size(6),
+            MacAddressFormat::BitReversed => formatter.separator(':').bit_reversed(true),
+        }
+    }
+}
+
+/// Displays a MacAddress in the layout of a MacFormatter
+///
+/// Returned by `MacFormatter::display`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacFormatterDisplay {
+    formatter: MacFormatter,
+    mac: MacAddress,
+}
+
+impl fmt::Display for MacFormatterDisplay {
+    /// Width, fill and alignment apply to the whole address
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = Buffer {
+            buf: [0; MAX_LEN],
+            len: 0,
+        };
+        self.formatter.write(&mut buf, &self.mac)?;
+        f.pad(as_str(&buf.buf[..buf.len]))
+    }
+}
+
+/// Stack buffer holding the output of a MacFormatter
+struct Buffer {
+    buf: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_format() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let formatter = MacFormatter::new();
+        assert_eq!("12-34-56-ab-cd-ef", formatter.format(&mac));
+        assert_eq!("12.34.56.ab.cd.ef", formatter.separator('.').format(&mac));
+        assert_eq!(
+            "12 34 56 AB CD EF",
+            formatter
+                .separator(' ')
+                .case(LetterCase::Upper)
+                .format(&mac)
+        );
+        assert_eq!("123-456-abc-def", formatter.group_size(3).format(&mac));
+        assert_eq!(
+            "12345∙6abcd∙ef",
+            formatter.group_size(5).separator('∙').format(&mac)
+        );
+        assert_eq!(
+            "MAC 1234.56AB.CDEF",
+            formatter
+                .group_size(4)
+                .separator('.')
+                .case(LetterCase::Upper)
+                .prefix("MAC ")
+                .format(&mac)
+        );
+        assert_eq!(
+            "  12_34_56_ab_cd_ef",
+            format!("  {}", formatter.separator('_').display(mac))
+        );
+        assert_eq!(
+            "[123456∙abcdef  ]",
+            format!(
+                "[{:<15}]",
+                formatter.group_size(6).separator('∙').display(mac)
+            )
+        );
+        assert_eq!(
+            "**0x123456abcdef",
+            format!(
+                "{:*>16}",
+                MacFormatter::from(MacAddressFormat::Hexadecimal).display(mac)
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_from_format() {
+        use super::MacAddressFormat::*;
+
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        for &fmt in &[
+            Canonical,
+            HexString,
+            DotNotation,
+            Hexadecimal,
+            BareHex,
+            HyphenQuad,
+            ColonHalves,
+            HyphenHalves,
+            BitReversed,
+            Irregular,
+        ] {
+            let formatter = MacFormatter::from(fmt);
+            assert_eq!(mac.to_string(fmt), formatter.format(&mac));
+            assert_eq!(Ok(mac), formatter.parse(&mac.to_string(fmt)));
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        use super::ParseError::*;
+
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let dotted = MacFormatter::new().separator('.');
+        assert_eq!(Ok(mac), dotted.parse("12.34.56.ab.cd.ef"));
+        assert_eq!(
+            Err(InvalidCharacter(9, 'A')),
+            dotted.parse("12.34.56.AB.cd.ef")
+        );
+        assert_eq!(
+            Err(InvalidSeparator(2, ':')),
+            dotted.parse("12:34:56:ab:cd:ef")
+        );
+        assert_eq!(
+            Err(InvalidGroupWidth(0, 3)),
+            dotted.parse("123.4.56.ab.cd.ef")
+        );
+        assert_eq!(
+            Err(InvalidGroupWidth(3, 1)),
+            dotted.parse("12.3.456.ab.cd.ef")
+        );
+        assert_eq!(
+            Err(InvalidByteCount(5, [0x12, 0x34, 0x56, 0xAB, 0xCD, 0])),
+            dotted.parse("12.34.56.ab.cd")
+        );
+        assert_eq!(
+            Err(InvalidGroupWidth(15, 1)),
+            dotted.parse("12.34.56.ab.cd.e")
+        );
+        assert_eq!(
+            Err(TrailingCharacters(17)),
+            dotted.parse("12.34.56.ab.cd.ef.")
+        );
+        assert_eq!(
+            Err(InvalidCharacter(0, 'g')),
+            dotted.parse("g2.34.56.ab.cd.ef")
+        );
+
+        let prefixed = MacFormatter::from(MacAddressFormat::Hexadecimal);
+        assert_eq!(Ok(mac), prefixed.parse("0x123456abcdef"));
+        assert_eq!(
+            Err(InvalidCharacter(1, 'X')),
+            prefixed.parse("0X123456abcdef")
+        );
+        assert_eq!(Err(InvalidLength(1)), prefixed.parse("0"));
+
+        let spaced = MacFormatter::new().group_size(3).separator(' ');
+        assert_eq!(Ok(mac), spaced.parse("123 456 abc def"));
+        assert_eq!(
+            Err(InvalidGroupWidth(8, 2)),
+            spaced.parse("123 456 ab cdef")
+        );
+        assert_eq!(Err(InvalidDigitCount(12, 9)), spaced.parse("123 456 abc"));
+        assert_eq!(
+            Err(InvalidByteCount(3, [0x12, 0x34, 0x56, 0, 0, 0])),
+            spaced.parse("123 456")
+        );
+    }
+}
@@ -6,17 +6,23 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! Represent and parse IEEE EUI-48 Media Access Control addresses
+//! Represent and parse IEEE EUI-48 Media Access Control addresses and EUI-64 addresses
 //! The IEEE claims trademarks on the names EUI-48 and EUI-64, in which EUI is an
 //! abbreviation for Extended Unique Identifier.
 
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![doc(
     html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk-v2.png",
     html_favicon_url = "https://www.rust-lang.org/favicon.ico",
     html_root_url = "https://doc.rust-lang.org/eui48/"
 )]
 
-extern crate regex;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "oui-db")]
+extern crate eui48_oui_db;
+#[cfg(feature = "rand")]
+extern crate rand;
 #[cfg(feature = "rustc-serialize")]
 extern crate rustc_serialize;
 #[cfg(feature = "serde")]
@@ -24,17 +30,60 @@ extern crate serde;
 #[cfg(feature = "serde_json")]
 extern crate serde_json;
 
-use std::default::Default;
-use std::error::Error;
-use std::fmt;
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::default::Default;
+use core::error::Error;
+use core::fmt;
+use core::net::Ipv6Addr;
+use core::str::FromStr;
+use core::sync::atomic::{AtomicU8, Ordering};
 
-use regex::Regex;
 #[cfg(feature = "rustc-serialize")]
 use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
 #[cfg(feature = "serde")]
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+#[macro_use]
+mod macros;
+mod assignment;
+mod bitwise;
+mod display;
+mod eui64;
+pub mod formats;
+mod formatter;
+mod matcher;
+mod oui;
+mod parser;
+mod prefix;
+#[cfg(feature = "rand")]
+mod random;
+mod range;
+#[cfg(feature = "std")]
+mod registry;
+mod slap;
+
+pub use assignment::{Cid, Oui, OuiMaM, OuiMaS};
+use display::fmt_hex;
+pub use display::{Eui64AddressDisplay, MacAddressDisplay};
+pub use eui64::{Eui64Address, Eui64Encapsulation};
+pub use formats::{FormatMarker, MacAddressWith};
+pub use formatter::{MacFormatter, MacFormatterDisplay};
+pub use matcher::{MacMatcher, MacMatcherDisplay, MatcherDialect};
+pub use oui::{Registry, Vendor};
+pub use parser::MacParser;
+pub use prefix::MacPrefix;
+#[cfg(feature = "rand")]
+pub use random::MacDistribution;
+pub use range::MacRange;
+#[cfg(feature = "std")]
+pub use registry::{OuiFileFormat, OuiLoadError, OuiRegistry};
+pub use slap::SlapQuadrant;
+
+#[doc(hidden)]
+pub use parser::parse_literal as __parse_literal;
+
 /// A 48-bit (6 byte) buffer containing the EUI address
 pub const EUI48LEN: usize = 6;
 pub type Eui48 = [u8; EUI48LEN];
@@ -51,26 +100,203 @@ pub struct MacAddress {
     eui: Eui48,
 }
 
+/// Declares MacAddressFormat together with the list of its variants in declaration order, so
+/// the value of `fmt as u8` stored in DEFAULT_FORMAT maps back without a hand-written match
+macro_rules! address_formats {
+    ($(#[$attr:meta])* pub enum $name:ident { $($(#[$doc:meta])* $variant:ident,)* }) => {
+        $(#[$attr])*
+        pub enum $name {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl $name {
+            /// Every format, indexed by its discriminant
+            const ALL: &'static [$name] = &[$($name::$variant),*];
+        }
+    };
+}
+
+address_formats! {
+    #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    /// Format to display MacAddress
+    ///
+    /// Hex digits are written in lowercase unless a `LetterCase` is given, as with
+    /// `MacAddress::to_string_with_case`. Uppercase Canonical is the IEEE and Windows `getmac`
+    /// notation, lowercase HexString the Linux, Juniper and PostgreSQL `macaddr` output.
+    #[non_exhaustive]
+    pub enum MacAddressFormat {
+        /// Use - notaion
+        Canonical,
+        /// Use : notation
+        HexString,
+        /// Use . notation
+        DotNotation,
+        /// Use 0x notation
+        Hexadecimal,
+        /// Use bare hex notation, without separators or prefix
+        BareHex,
+        /// Use - between groups of four digits, '1234-56ab-cdef' (HP, H3C, Huawei)
+        HyphenQuad,
+        /// Use : between two halves, '123456:abcdef' (PostgreSQL macaddr input)
+        ColonHalves,
+        /// Use - between two halves, '123456-abcdef' (PostgreSQL macaddr input)
+        HyphenHalves,
+        /// Use : notation with the bits of each octet reversed, '48:2c:6a:d5:b3:f7' for
+        /// '12-34-56-ab-cd-ef' (IEEE 802.5 Token Ring and FDDI). Parsers read it as HexString;
+        /// apply `MacAddress::bit_reversed` to the result. Displayed as HexString when it is
+        /// the default format.
+        BitReversed,
+        /// Mixed separators or single digit octets, as tolerated by the parser. Displayed as
+        /// Canonical.
+        Irregular,
+    }
+}
+
+impl MacAddressFormat {
+    /// Inverse of `fmt as u8`, for the value stored in DEFAULT_FORMAT
+    const fn from_u8(v: u8) -> MacAddressFormat {
+        MacAddressFormat::ALL[v as usize]
+    }
+
+    /// Returns the notation Display and serialization use when this is the default format
+    ///
+    /// BitReversed would read back as a different address, so it falls back to HexString, the
+    /// notation it is parsed as.
+    pub(crate) const fn parseable(self) -> MacAddressFormat {
+        match self {
+            MacAddressFormat::BitReversed => MacAddressFormat::HexString,
+            MacAddressFormat::Irregular => MacAddressFormat::Canonical,
+            fmt => fmt,
+        }
+    }
+}
+
+/// The process wide Display format, initially HexString with the `disp_hexstring`
+/// feature and Canonical otherwise
+static DEFAULT_FORMAT: AtomicU8 = AtomicU8::new(if cfg!(feature = "disp_hexstring") {
+    MacAddressFormat::HexString as u8
+} else {
+    MacAddressFormat::Canonical as u8
+});
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-/// Format to display MacAddress
-pub enum MacAddressFormat {
-    /// Use - notaion
-    Canonical,
-    /// Use : notation
-    HexString,
-    /// Use . notation
-    DotNotation,
-    /// Use 0x notation
-    Hexadecimal,
+/// Letter case of the hex digits a-f
+pub enum LetterCase {
+    /// Lowercase hex digits, 'ab:cd:ef'
+    Lower,
+    /// Uppercase hex digits, 'AB:CD:EF'
+    Upper,
+}
+
+impl LetterCase {
+    /// Detects the case of the hex digits a-f in `s`
+    ///
+    /// Returns None if `s` has no hex letters or mixes both cases.
+    pub fn detect(s: &str) -> Option<LetterCase> {
+        let lower = s.bytes().any(|c| matches!(c, b'a'..=b'f'));
+        let upper = s.bytes().any(|c| matches!(c, b'A'..=b'F'));
+        match (lower, upper) {
+            (true, false) => Some(LetterCase::Lower),
+            (false, true) => Some(LetterCase::Upper),
+            _ => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug, Ord, PartialOrd, Hash)]
 /// Parsing errors
+///
+/// Offsets are in bytes from the start of the input string.
+#[non_exhaustive]
 pub enum ParseError {
     /// Length is incorrect (should be 11 to 17)
     InvalidLength(usize),
     /// The input string is invalid, usize bytes were found, and we put up to 6 bytes into Eui48
     InvalidByteCount(usize, Eui48),
+    /// Length of an EUI-64 string is incorrect (should be 15 to 23)
+    InvalidEui64Length(usize),
+    /// The input string is invalid, usize bytes were found, and we put up to 8 bytes into Eui64
+    InvalidEui64ByteCount(usize, Eui64),
+    /// The input string is not written in any of the accepted notations
+    UnknownFormat,
+    /// The input string is written in a notation the parser was told to reject
+    DisallowedFormat(MacAddressFormat),
+    /// A character that is neither a hex digit nor a separator was found at the offset
+    InvalidCharacter(usize, char),
+    /// A separator was found at the offset where the notation does not allow it
+    InvalidSeparator(usize, char),
+    /// A separator different from the first one was found at the offset
+    InconsistentSeparators(usize, char),
+    /// The group of hex digits starting at the offset has the wrong number of digits
+    InvalidGroupWidth(usize, usize),
+    /// A complete address is followed by more characters, starting at the offset
+    TrailingCharacters(usize),
+    /// The length of a MacPrefix is greater than 48, or given without '/'
+    InvalidPrefixLength(usize),
+    /// The one bits of a MacPrefix mask are not contiguous from the first bit
+    InvalidMask(MacAddress),
+    /// An identifier like '00-1B-21' should have the first usize hex digits, found the second
+    InvalidDigitCount(usize, usize),
+    /// A Company ID must have the local bit set and the group bit clear
+    InvalidCid(u32),
+}
+
+impl ParseError {
+    /// Returns the byte offset of the error in the input string, if known
+    pub fn position(&self) -> Option<usize> {
+        match *self {
+            ParseError::InvalidCharacter(pos, _)
+            | ParseError::InvalidSeparator(pos, _)
+            | ParseError::InconsistentSeparators(pos, _)
+            | ParseError::InvalidGroupWidth(pos, _)
+            | ParseError::TrailingCharacters(pos) => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// Returns a Display adapter printing the error, followed by the input with a caret under
+    /// the offset of the error
+    ///
+    /// ```text
+    /// Invalid character; found 'g' at offset 16
+    /// 12:34:56:ab:cd:eg
+    ///                 ^
+    /// ```
+    pub fn with_input<'a>(&'a self, input: &'a str) -> ParseErrorContext<'a> {
+        ParseErrorContext { error: self, input }
+    }
+
+    /// Moves the offset of the error by `offset` bytes
+    fn offset_by(self, offset: usize) -> ParseError {
+        match self {
+            ParseError::InvalidCharacter(pos, c) => ParseError::InvalidCharacter(pos + offset, c),
+            ParseError::InvalidSeparator(pos, c) => ParseError::InvalidSeparator(pos + offset, c),
+            ParseError::InconsistentSeparators(pos, c) => {
+                ParseError::InconsistentSeparators(pos + offset, c)
+            }
+            ParseError::InvalidGroupWidth(pos, w) => ParseError::InvalidGroupWidth(pos + offset, w),
+            ParseError::TrailingCharacters(pos) => ParseError::TrailingCharacters(pos + offset),
+            err => err,
+        }
+    }
+}
+
+/// Display adapter for a ParseError and the input that caused it, see `ParseError::with_input`
+#[derive(Copy, Clone, Debug)]
+pub struct ParseErrorContext<'a> {
+    error: &'a ParseError,
+    input: &'a str,
+}
+
+/// An integer is too large for the address it was converted to, see `MacAddress::from_u64`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OutOfRangeError(u128);
+
+impl OutOfRangeError {
+    /// Returns the integer that did not fit
+    pub const fn value(&self) -> u128 {
+        self.0
+    }
 }
 
 impl MacAddress {
@@ -92,12 +318,12 @@ impl MacAddress {
     }
 
     /// Returns empty EUI-48 address
-    pub fn nil() -> MacAddress {
+    pub const fn nil() -> MacAddress {
         MacAddress { eui: [0; EUI48LEN] }
     }
 
     /// Returns 'ff:ff:ff:ff:ff:ff', a MAC broadcast address
-    pub fn broadcast() -> MacAddress {
+    pub const fn broadcast() -> MacAddress {
         MacAddress {
             eui: [0xFF; EUI48LEN],
         }
@@ -134,119 +360,234 @@ impl MacAddress {
     }
 
     /// Returns a String representation in the format '00-00-00-00-00-00'
+    #[cfg(feature = "alloc")]
     pub fn to_canonical(&self) -> String {
-        format!(
-            "{:02x}-{:02x}-{:02x}-{:02x}-{:02x}-{:02x}",
-            self.eui[0], self.eui[1], self.eui[2], self.eui[3], self.eui[4], self.eui[5]
-        )
+        self.to_string(MacAddressFormat::Canonical)
     }
 
     /// Returns a String representation in the format '00:00:00:00:00:00'
+    #[cfg(feature = "alloc")]
     pub fn to_hex_string(&self) -> String {
-        format!(
-            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-            self.eui[0], self.eui[1], self.eui[2], self.eui[3], self.eui[4], self.eui[5]
-        )
+        self.to_string(MacAddressFormat::HexString)
     }
 
     /// Returns a String representation in the format '0000.0000.0000'
+    #[cfg(feature = "alloc")]
     pub fn to_dot_string(&self) -> String {
-        format!(
-            "{:02x}{:02x}.{:02x}{:02x}.{:02x}{:02x}",
-            self.eui[0], self.eui[1], self.eui[2], self.eui[3], self.eui[4], self.eui[5]
-        )
+        self.to_string(MacAddressFormat::DotNotation)
     }
 
     /// Returns a String representation in the format '0x000000000000'
+    #[cfg(feature = "alloc")]
     pub fn to_hexadecimal(&self) -> String {
-        format!(
-            "0x{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
-            self.eui[0], self.eui[1], self.eui[2], self.eui[3], self.eui[4], self.eui[5]
-        )
+        self.to_string(MacAddressFormat::Hexadecimal)
     }
 
     /// Returns a String representation in the EUI-64 interface ID format '0000:00ff:fe00:0000'
+    #[cfg(feature = "alloc")]
     pub fn to_interfaceid(&self) -> String {
-        format!(
-            "{:02x}{:02x}:{:02x}ff:fe{:02x}:{:02x}{:02x}",
-            (self.eui[0] ^ 0x02),
-            self.eui[1],
-            self.eui[2],
-            self.eui[3],
-            self.eui[4],
-            self.eui[5]
-        )
+        let mut buf = [0; 19];
+        let len = display::encode_interfaceid(self, &mut buf);
+        String::from(display::as_str(&buf[..len]))
     }
 
     /// Returns a String representation in the IPv6 link local format 'fe80::0000:00ff:fe00:0000'
+    #[cfg(feature = "alloc")]
     pub fn to_link_local(&self) -> String {
-        format!(
-            "fe80::{:02x}{:02x}:{:02x}ff:fe{:02x}:{:02x}{:02x}",
-            (self.eui[0] ^ 0x02),
-            self.eui[1],
-            self.eui[2],
-            self.eui[3],
-            self.eui[4],
-            self.eui[5]
-        )
+        let mut buf = [0; 25];
+        buf[..6].copy_from_slice(b"fe80::");
+        let len = display::encode_interfaceid(self, &mut buf[6..]);
+        String::from(display::as_str(&buf[..6 + len]))
+    }
+
+    /// Returns the IPv6 link local address 'fe80::xxxx:xxff:fexx:xxxx' for this address
+    pub fn to_link_local_ipv6(&self) -> Ipv6Addr {
+        let mut octets = [0; 16];
+        octets[0] = 0xfe;
+        octets[1] = 0x80;
+        octets[8..].copy_from_slice(self.to_modified_eui64().as_bytes());
+        Ipv6Addr::from(octets)
+    }
+
+    /// Returns the SLAAC address formed from `prefix` and the modified EUI-64 of this address
+    ///
+    /// Bits of `prefix` beyond `prefix_len` are cleared. Returns None if `prefix_len` is
+    /// greater than 64, as the interface identifier takes the low 64 bits.
+    pub fn to_slaac_address(&self, prefix: Ipv6Addr, prefix_len: u8) -> Option<Ipv6Addr> {
+        if prefix_len > 64 {
+            return None;
+        }
+        let mask = u128::MAX
+            .checked_shl(128 - u32::from(prefix_len))
+            .unwrap_or(0);
+        let mut octets = (u128::from(prefix) & mask).to_be_bytes();
+        octets[8..].copy_from_slice(self.to_modified_eui64().as_bytes());
+        Some(Ipv6Addr::from(octets))
+    }
+
+    /// Recovers the MacAddress from an IPv6 address with a modified EUI-64 interface identifier
+    ///
+    /// Returns None if the interface identifier does not carry the 'ff:fe' marker.
+    pub fn from_ipv6_eui64(addr: &Ipv6Addr) -> Option<MacAddress> {
+        let mut iid = [0; EUI64LEN];
+        iid.copy_from_slice(&addr.octets()[8..]);
+        match MacAddress::from_modified_eui64(&Eui64Address::new(iid)) {
+            Some((mac, Eui64Encapsulation::Eui48)) => Some(mac),
+            _ => None,
+        }
     }
 
     /// Returns a String in the format selected by fmt
+    #[cfg(feature = "alloc")]
     pub fn to_string(&self, fmt: MacAddressFormat) -> String {
-        match fmt {
-            MacAddressFormat::Canonical => self.to_canonical(),
-            MacAddressFormat::HexString => self.to_hex_string(),
-            MacAddressFormat::DotNotation => self.to_dot_string(),
-            MacAddressFormat::Hexadecimal => self.to_hexadecimal(),
-        }
+        let mut buf = [0; 17];
+        String::from(self.write_to(&mut buf, fmt))
+    }
+
+    /// Returns a String in the format selected by fmt, with hex digits in the given case
+    #[cfg(feature = "alloc")]
+    pub fn to_string_with_case(&self, fmt: MacAddressFormat, case: LetterCase) -> String {
+        let mut buf = [0; 17];
+        let len = display::encode(&self.eui, &mut buf, fmt, case);
+        String::from(display::as_str(&buf[..len]))
+    }
+
+    /// Returns an adapter that displays the address in the format selected by fmt
+    ///
+    /// Unlike `to_string`, the adapter does not allocate, so it can be handed straight
+    /// to `write!` or a logging macro. Use `MacAddressDisplay::with_case` for uppercase digits.
+    pub fn display(&self, fmt: MacAddressFormat) -> MacAddressDisplay {
+        MacAddressDisplay::new(*self, fmt)
+    }
+
+    /// Renders the address into `buf` in the format selected by fmt
+    ///
+    /// Returns the rendered part of `buf`; 17 bytes are enough for every notation.
+    pub fn write_to<'a>(&self, buf: &'a mut [u8; 17], fmt: MacAddressFormat) -> &'a str {
+        let len = display::encode(&self.eui, buf, fmt, LetterCase::Lower);
+        display::as_str(&buf[..len])
     }
 
     /// Parses a String representation from any format supported
+    ///
+    /// Parsing is lenient, see `MacParser` for validating the notation of the input.
     pub fn parse_str(s: &str) -> Result<MacAddress, ParseError> {
-        let re = Regex::new("(0x)?([0-9a-fA-F]{1,2})[:.-]?").unwrap();
-        let mut eui: Eui48 = [0; EUI48LEN];
+        MacParser::lenient().parse(s)
+    }
 
-        match s.len() {
-            11..=17 => {}
-            _ => {
-                return Err(ParseError::InvalidLength(s.len()));
-            }
+    /// Parses a String representation like `parse_str`, also returning the notation used
+    ///
+    /// Input that `parse_str` only accepts through its leniency is reported as
+    /// `MacAddressFormat::Irregular`. Use `LetterCase::detect` for the case of the hex digits.
+    pub fn parse_with_format(s: &str) -> Result<(MacAddress, MacAddressFormat), ParseError> {
+        MacParser::lenient().parse_with_format(s)
+    }
+
+    /// Return the internal structure as a slice of bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.eui
+    }
+
+    /// Returns the address with the bits of each octet reversed
+    ///
+    /// This converts between the canonical (Ethernet) bit order and the non-canonical order
+    /// of IEEE 802.5 Token Ring and FDDI, in either direction.
+    pub const fn bit_reversed(&self) -> MacAddress {
+        MacAddress::from_bit_reversed(self.eui)
+    }
+
+    /// Create a MacAddress from the bytes of an address in bit-reversed (non-canonical) order
+    pub const fn from_bit_reversed(eui: Eui48) -> MacAddress {
+        let mut canonical = eui;
+        let mut i = 0;
+        while i < EUI48LEN {
+            canonical[i] = eui[i].reverse_bits();
+            i += 1;
         }
+        MacAddress { eui: canonical }
+    }
 
+    /// Returns an array in Eui48. Works as an inverse function of new()
+    pub fn to_array(&self) -> Eui48 {
+        self.eui
+    }
+
+    /// Returns the address as a 48-bit integer, first octet most significant
+    pub const fn to_u64(&self) -> u64 {
+        let mut bytes = [0; 8];
         let mut i = 0;
-        for caps in re.captures_iter(s) {
-            // Fill the array and keep counting for InvalidByteCount
-            if i < EUI48LEN {
-                let matched_byte = caps.get(2).unwrap().as_str();
-                eui[i] = u8::from_str_radix(matched_byte, 16).unwrap();
-            }
+        while i < EUI48LEN {
+            bytes[i + 2] = self.eui[i];
             i += 1;
         }
+        u64::from_be_bytes(bytes)
+    }
 
-        if i != EUI48LEN {
-            return Err(ParseError::InvalidByteCount(i, eui));
+    /// Create a MacAddress from a 48-bit integer, first octet most significant
+    ///
+    /// Returns `OutOfRangeError` if `value` does not fit in 48 bits.
+    pub const fn from_u64(value: u64) -> Result<MacAddress, OutOfRangeError> {
+        if value >> 48 != 0 {
+            return Err(OutOfRangeError(value as u128));
         }
+        let bytes = value.to_be_bytes();
+        Ok(MacAddress::new([
+            bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+        ]))
+    }
 
-        Ok(MacAddress::new(eui))
+    /// Returns the octets in transmission order, the same as `to_array`
+    pub const fn to_be_bytes(&self) -> Eui48 {
+        self.eui
     }
 
-    /// Return the internal structure as a slice of bytes
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.eui
+    /// Returns the octets in reverse order, as stored by little-endian hardware registers
+    pub const fn to_le_bytes(&self) -> Eui48 {
+        let mut eui = self.eui;
+        let mut i = 0;
+        while i < EUI48LEN {
+            eui[i] = self.eui[EUI48LEN - 1 - i];
+            i += 1;
+        }
+        eui
     }
 
-    /// Returns an array in Eui48. Works as an inverse function of new()
-    pub fn to_array(&self) -> Eui48 {
-        self.eui
+    /// Create a MacAddress from octets in transmission order, the same as `new`
+    pub const fn from_be_bytes(eui: Eui48) -> MacAddress {
+        MacAddress::new(eui)
+    }
+
+    /// Create a MacAddress from octets in reverse order, the inverse of `to_le_bytes`
+    pub const fn from_le_bytes(eui: Eui48) -> MacAddress {
+        MacAddress::new(MacAddress::new(eui).to_le_bytes())
     }
 
-    /// Returns Display MacAddressFormat, determined at compile time.
+    /// Returns Display MacAddressFormat, as set by `set_default_format`
+    ///
+    /// Until it is set, the format is HexString if the `disp_hexstring` feature is enabled
+    /// and Canonical otherwise.
     pub fn get_display_format() -> MacAddressFormat {
-        if cfg!(feature = "disp_hexstring") {
-            MacAddressFormat::HexString
-        } else {
-            MacAddressFormat::Canonical
-        }
+        MacAddressFormat::from_u8(DEFAULT_FORMAT.load(Ordering::Relaxed))
+    }
+
+    /// Sets the Display MacAddressFormat for the whole process
+    ///
+    /// This also applies to serde serialization and to `Eui64Address`. Use `MacAddressWith`
+    /// to pin the notation of a single value instead.
+    ///
+    /// A BitReversed default is written as HexString and an Irregular one as Canonical, so
+    /// the output parses back to the same address.
+    ///
+    /// ```
+    /// use eui48::{MacAddress, MacAddressFormat};
+    ///
+    /// let mac = MacAddress::new([0x12, 0x34, 0x56, 0xab, 0xcd, 0xef]);
+    /// MacAddress::set_default_format(MacAddressFormat::DotNotation);
+    /// assert_eq!(MacAddressFormat::DotNotation, MacAddress::get_display_format());
+    /// assert_eq!("1234.56ab.cdef", format!("{}", mac));
+    /// ```
+    pub fn set_default_format(fmt: MacAddressFormat) {
+        DEFAULT_FORMAT.store(fmt as u8, Ordering::Relaxed);
     }
 }
 
@@ -258,6 +599,39 @@ impl FromStr for MacAddress {
     }
 }
 
+impl From<MacAddress> for u64 {
+    /// The address as a 48-bit integer, see `MacAddress::to_u64`
+    fn from(mac: MacAddress) -> u64 {
+        mac.to_u64()
+    }
+}
+
+impl From<MacAddress> for u128 {
+    /// The address as a 48-bit integer, see `MacAddress::to_u64`
+    fn from(mac: MacAddress) -> u128 {
+        u128::from(mac.to_u64())
+    }
+}
+
+impl TryFrom<u64> for MacAddress {
+    type Error = OutOfRangeError;
+    /// Create a MacAddress from a 48-bit integer, see `MacAddress::from_u64`
+    fn try_from(value: u64) -> Result<MacAddress, OutOfRangeError> {
+        MacAddress::from_u64(value)
+    }
+}
+
+impl TryFrom<u128> for MacAddress {
+    type Error = OutOfRangeError;
+    /// Create a MacAddress from a 48-bit integer, see `MacAddress::from_u64`
+    fn try_from(value: u128) -> Result<MacAddress, OutOfRangeError> {
+        match u64::try_from(value) {
+            Ok(value) => MacAddress::from_u64(value),
+            Err(_) => Err(OutOfRangeError(value)),
+        }
+    }
+}
+
 impl Default for MacAddress {
     /// Create a Default MacAddress (00-00-00-00-00-00)
     fn default() -> MacAddress {
@@ -268,19 +642,43 @@ impl Default for MacAddress {
 impl fmt::Debug for MacAddress {
     /// Debug format for MacAddress is HexString notation
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 17];
         write!(
             f,
             "MacAddress(\"{}\")",
-            self.to_string(MacAddressFormat::HexString)
+            self.write_to(&mut buf, MacAddressFormat::HexString)
         )
     }
 }
 
 impl fmt::Display for MacAddress {
     /// Display format is canonical format (00-00-00-00-00-00) by default
+    ///
+    /// The alternate form `{:#}` selects HexString notation (00:00:00:00:00:00),
+    /// and width, fill and alignment are honoured. A BitReversed default is written as
+    /// HexString, so the output can be parsed back.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let disp_fmt = MacAddress::get_display_format();
-        write!(f, "{}", self.to_string(disp_fmt))
+        let fmt = if f.alternate() {
+            MacAddressFormat::HexString
+        } else {
+            MacAddress::get_display_format().parseable()
+        };
+        let mut buf = [0; 17];
+        f.pad(self.write_to(&mut buf, fmt))
+    }
+}
+
+impl fmt::LowerHex for MacAddress {
+    /// Bare lower case hex digits, with a '0x' prefix in alternate form
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_hex(&self.eui, f, LetterCase::Lower)
+    }
+}
+
+impl fmt::UpperHex for MacAddress {
+    /// Bare upper case hex digits, with a '0x' prefix in alternate form
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_hex(&self.eui, f, LetterCase::Upper)
     }
 }
 
@@ -297,12 +695,89 @@ impl fmt::Display for ParseError {
                 f,
                 "Invalid byte count; Matched `{}` bytes ({:?})",
                 found,
-                &eui[..found]
+                &eui[..found.min(EUI48LEN)]
+            ),
+            ParseError::InvalidEui64Length(found) => write!(
+                f,
+                "Invalid length; expecting 15 to 23 chars, found {}",
+                found
+            ),
+            ParseError::InvalidEui64ByteCount(found, eui) => write!(
+                f,
+                "Invalid byte count; Matched `{}` bytes ({:?})",
+                found,
+                &eui[..found.min(EUI64LEN)]
+            ),
+            ParseError::UnknownFormat => write!(f, "Invalid format; not a supported notation"),
+            ParseError::DisallowedFormat(fmt) => {
+                write!(f, "Invalid format; {:?} notation is not accepted", fmt)
+            }
+            ParseError::InvalidCharacter(pos, c) => {
+                write!(f, "Invalid character; found {:?} at offset {}", c, pos)
+            }
+            ParseError::InvalidSeparator(pos, c) => {
+                write!(f, "Invalid separator; found {:?} at offset {}", c, pos)
+            }
+            ParseError::InconsistentSeparators(pos, c) => write!(
+                f,
+                "Inconsistent separators; found {:?} at offset {}",
+                c, pos
+            ),
+            ParseError::InvalidGroupWidth(pos, width) => write!(
+                f,
+                "Invalid group width; found {} hex digits at offset {}",
+                width, pos
+            ),
+            ParseError::TrailingCharacters(pos) => {
+                write!(f, "Trailing characters; found at offset {}", pos)
+            }
+            ParseError::InvalidPrefixLength(found) => write!(
+                f,
+                "Invalid prefix length; expecting 0 to 48, found {}",
+                found
+            ),
+            ParseError::InvalidMask(mask) => {
+                write!(f, "Invalid mask; {:#} is not a prefix mask", mask)
+            }
+            ParseError::InvalidDigitCount(expected, found) => write!(
+                f,
+                "Invalid digit count; expecting {} hex digits, found {}",
+                expected, found
+            ),
+            ParseError::InvalidCid(value) => write!(
+                f,
+                "Invalid company ID; {:06X} is not a locally administered unicast prefix",
+                value
             ),
         }
     }
 }
 
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Out of range; {:#x} does not fit in the address", self.0)
+    }
+}
+
+impl Error for OutOfRangeError {}
+
+impl<'a> fmt::Display for ParseErrorContext<'a> {
+    /// Print the error, the input and a caret under the offset of the error
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(prefix) = self.error.position().and_then(|pos| self.input.get(..pos)) {
+            write!(
+                f,
+                "\n{}\n{:width$}^",
+                self.input,
+                "",
+                width = prefix.chars().count()
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl Error for ParseError {
     /// Human readable description for ParseError enum
     fn description(&self) -> &str {
@@ -314,7 +789,7 @@ impl Error for ParseError {
 impl Encodable for MacAddress {
     /// Encode a MacAddress using the default format
     fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
-        let disp_fmt = MacAddress::get_display_format();
+        let disp_fmt = MacAddress::get_display_format().parseable();
         e.emit_str(&self.to_string(disp_fmt))
     }
 }
@@ -332,8 +807,7 @@ impl Decodable for MacAddress {
 impl Serialize for MacAddress {
     /// Serialize a MacAddress in the default format using the serde crate
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let disp_fmt = MacAddress::get_display_format();
-        serializer.serialize_str(&self.to_string(disp_fmt))
+        serializer.collect_str(self)
     }
 }
 
@@ -358,7 +832,7 @@ impl<'de> Deserialize<'de> for MacAddress {
             }
 
             fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
-                value.parse().map_err(|err| E::custom(&format!("{}", err)))
+                value.parse().map_err(E::custom)
             }
         }
         deserializer.deserialize_str(MacAddressVisitor)
@@ -388,7 +862,7 @@ impl<'de> Deserialize<'de> for MacAddress {
 // ************** TESTS BEGIN HERE ***************
 #[cfg(test)]
 mod tests {
-    use super::{Eui48, MacAddress, MacAddressFormat, ParseError};
+    use super::{Eui48, MacAddress, MacAddressFormat, OutOfRangeError, ParseError};
 
     #[test]
     fn test_new() {
@@ -399,6 +873,41 @@ mod tests {
     }
 
     #[test]
+    fn test_integers() {
+        use std::convert::TryFrom;
+
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(0x1234_56AB_CDEF, mac.to_u64());
+        assert_eq!(0x1234_56AB_CDEF_u64, u64::from(mac));
+        assert_eq!(0x1234_56AB_CDEF_u128, u128::from(mac));
+        assert_eq!(Ok(mac), MacAddress::from_u64(0x1234_56AB_CDEF));
+        assert_eq!(Ok(mac), MacAddress::try_from(0x1234_56AB_CDEF_u64));
+        assert_eq!(Ok(mac), MacAddress::try_from(0x1234_56AB_CDEF_u128));
+        assert_eq!(
+            Ok(MacAddress::broadcast()),
+            MacAddress::from_u64(0xFFFF_FFFF_FFFF)
+        );
+        assert_eq!(
+            Err(OutOfRangeError(0x1_0000_0000_0000)),
+            MacAddress::from_u64(1 << 48)
+        );
+        assert_eq!(
+            Some(1 << 64),
+            MacAddress::try_from(1_u128 << 64).err().map(|e| e.value())
+        );
+        assert_eq!(
+            "Out of range; 0x1000000000000 does not fit in the address",
+            OutOfRangeError(1 << 48).to_string()
+        );
+
+        assert_eq!(mac.to_array(), mac.to_be_bytes());
+        assert_eq!([0xEF, 0xCD, 0xAB, 0x56, 0x34, 0x12], mac.to_le_bytes());
+        assert_eq!(mac, MacAddress::from_le_bytes(mac.to_le_bytes()));
+        assert_eq!(mac, MacAddress::from_be_bytes(mac.to_be_bytes()));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn test_from_bytes() {
         assert_eq!(
             "12:34:56:ab:cd:ef",
@@ -410,6 +919,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_nil() {
         let nil = MacAddress::nil();
         let not_nil = MacAddress::broadcast();
@@ -425,6 +935,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_broadcast() {
         let broadcast = MacAddress::broadcast();
         let not_broadcast = MacAddress::nil();
@@ -450,6 +961,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_is_unicast() {
         let mac_u = MacAddress::parse_str("FE:00:5E:AB:CD:EF").unwrap();
         let mac_m = MacAddress::parse_str("01:00:5E:AB:CD:EF").unwrap();
@@ -464,6 +976,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_is_multicast() {
         let mac_u = MacAddress::parse_str("FE:00:5E:AB:CD:EF").unwrap();
         let mac_m = MacAddress::parse_str("01:00:5E:AB:CD:EF").unwrap();
@@ -478,6 +991,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_is_universal() {
         let universal = MacAddress::parse_str("11:24:56:AB:CD:EF").unwrap();
         let not_universal = MacAddress::parse_str("12:24:56:AB:CD:EF").unwrap();
@@ -487,6 +1001,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_is_local() {
         let local = MacAddress::parse_str("06:34:56:AB:CD:EF").unwrap();
         let not_local = MacAddress::parse_str("00:34:56:AB:CD:EF").unwrap();
@@ -496,6 +1011,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_canonical() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -503,6 +1019,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_hex_string() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -510,6 +1027,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_dot_string() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -517,6 +1035,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_hexadecimal() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -524,6 +1043,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_interfaceid() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -531,6 +1051,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_link_local() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -538,6 +1059,66 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
+    fn test_to_link_local_ipv6() {
+        use std::net::Ipv6Addr;
+
+        let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
+        let mac = MacAddress::new(eui);
+        let addr = mac.to_link_local_ipv6();
+        assert_eq!("fe80::1034:56ff:feab:cdef", addr.to_string());
+        assert_eq!(addr, mac.to_link_local().parse::<Ipv6Addr>().unwrap());
+    }
+
+    #[test]
+    fn test_to_slaac_address() {
+        let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
+        let mac = MacAddress::new(eui);
+        let prefix = "2001:db8:1:2::".parse().unwrap();
+        assert_eq!(
+            "2001:db8:1:2:1034:56ff:feab:cdef",
+            mac.to_slaac_address(prefix, 64).unwrap().to_string()
+        );
+        let prefix = "2001:db8:1:2:3:4:5:6".parse().unwrap();
+        assert_eq!(
+            "2001:db8:1:2:1034:56ff:feab:cdef",
+            mac.to_slaac_address(prefix, 64).unwrap().to_string()
+        );
+        assert_eq!(
+            "2001:db8::1034:56ff:feab:cdef",
+            mac.to_slaac_address(prefix, 32).unwrap().to_string()
+        );
+        assert_eq!(
+            "::1034:56ff:feab:cdef",
+            mac.to_slaac_address(prefix, 0).unwrap().to_string()
+        );
+        assert_eq!(None, mac.to_slaac_address(prefix, 65));
+    }
+
+    #[test]
+    fn test_from_ipv6_eui64() {
+        let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
+        let mac = MacAddress::new(eui);
+        assert_eq!(
+            Some(mac),
+            MacAddress::from_ipv6_eui64(&mac.to_link_local_ipv6())
+        );
+        assert_eq!(
+            Some(mac),
+            MacAddress::from_ipv6_eui64(&"2001:db8::1034:56ff:feab:cdef".parse().unwrap())
+        );
+        assert_eq!(
+            None,
+            MacAddress::from_ipv6_eui64(&"2001:db8::1034:56ff:ffab:cdef".parse().unwrap())
+        );
+        assert_eq!(
+            None,
+            MacAddress::from_ipv6_eui64(&"2001:db8::1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn test_to_string() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
         let mac = MacAddress::new(eui);
@@ -557,9 +1138,79 @@ mod tests {
             "12-34-56-ab-cd-ef",
             mac.to_string(MacAddressFormat::Canonical)
         );
+        assert_eq!("123456abcdef", mac.to_string(MacAddressFormat::BareHex));
+        assert_eq!(
+            "12-34-56-ab-cd-ef",
+            mac.to_string(MacAddressFormat::Irregular)
+        );
+        assert_eq!(
+            "1234-56ab-cdef",
+            mac.to_string(MacAddressFormat::HyphenQuad)
+        );
+        assert_eq!(
+            "123456:abcdef",
+            mac.to_string(MacAddressFormat::ColonHalves)
+        );
+        assert_eq!(
+            "123456-abcdef",
+            mac.to_string(MacAddressFormat::HyphenHalves)
+        );
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
+    fn test_bit_reversed() {
+        use super::LetterCase;
+
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let reversed = MacAddress::new([0x48, 0x2C, 0x6A, 0xD5, 0xB3, 0xF7]);
+        assert_eq!(reversed, mac.bit_reversed());
+        assert_eq!(mac, reversed.bit_reversed());
+        assert_eq!(mac, MacAddress::from_bit_reversed(reversed.to_array()));
+        assert_eq!(
+            "48:2c:6a:d5:b3:f7",
+            mac.to_string(MacAddressFormat::BitReversed)
+        );
+        assert_eq!(
+            "48:2C:6A:D5:B3:F7",
+            mac.to_string_with_case(MacAddressFormat::BitReversed, LetterCase::Upper)
+        );
+        assert_eq!(
+            MacAddress::broadcast(),
+            MacAddress::broadcast().bit_reversed()
+        );
+        // The group bit of the first octet moves to the most significant bit
+        assert!(MacAddress::new([0x80, 0, 0, 0, 0, 0])
+            .bit_reversed()
+            .is_multicast());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_to_string_with_case() {
+        use super::LetterCase;
+
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            "12-34-56-AB-CD-EF",
+            mac.to_string_with_case(MacAddressFormat::Canonical, LetterCase::Upper)
+        );
+        assert_eq!(
+            "0x123456ABCDEF",
+            mac.to_string_with_case(MacAddressFormat::Hexadecimal, LetterCase::Upper)
+        );
+        assert_eq!(
+            "1234-56AB-CDEF",
+            mac.to_string_with_case(MacAddressFormat::HyphenQuad, LetterCase::Upper)
+        );
+        assert_eq!(
+            "1234.56ab.cdef",
+            mac.to_string_with_case(MacAddressFormat::DotNotation, LetterCase::Lower)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn test_parse_str() {
         use super::ParseError::*;
 
@@ -652,6 +1303,49 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_parse_with_format() {
+        use super::MacAddressFormat::*;
+
+        let mac = MacAddress::parse_str("12:34:56:AB:CD:EF").unwrap();
+        for &(s, fmt) in &[
+            ("12-34-56-AB-CD-EF", Canonical),
+            ("12:34:56:ab:cd:ef", HexString),
+            ("1234.56AB.CDEF", DotNotation),
+            ("0x123456abcdef", Hexadecimal),
+            ("123456ABCDEF", BareHex),
+            ("1234-56ab-cdef", HyphenQuad),
+            ("123456:abcdef", ColonHalves),
+            ("123456-ABCDEF", HyphenHalves),
+            ("12:34-56:ab:cd:ef", Irregular),
+            ("!12:34:56:ab:cdef", Irregular),
+        ] {
+            assert_eq!(Ok((mac, fmt)), MacAddress::parse_with_format(s));
+            assert_eq!(mac.to_string(fmt), mac.to_string(fmt).to_lowercase());
+        }
+        assert_eq!(
+            Ok((MacAddress::new([1, 2, 3, 0xA, 0xB, 0xC]), Irregular)),
+            MacAddress::parse_with_format("1:2:3:a:b:c")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidLength(0)),
+            MacAddress::parse_with_format("")
+        );
+    }
+
+    #[test]
+    fn test_letter_case() {
+        use super::LetterCase::{self, *};
+
+        assert_eq!(Some(Upper), LetterCase::detect("12:34:56:AB:CD:EF"));
+        assert_eq!(Some(Lower), LetterCase::detect("12:34:56:ab:cd:ef"));
+        assert_eq!(Some(Lower), LetterCase::detect("0x123456abcdef"));
+        assert_eq!(Some(Upper), LetterCase::detect("0x123456ABCDEF"));
+        assert_eq!(None, LetterCase::detect("12:34:56:aB:CD:EF"));
+        assert_eq!(None, LetterCase::detect("12:34:56:78:90:12"));
+    }
+
     #[test]
     fn test_as_bytes() {
         let mac = MacAddress::broadcast();
@@ -682,6 +1376,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "rustc-serialize")]
     fn test_serialize() {
         use rustc_serialize::json;
 
@@ -695,6 +1390,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "rustc-serialize")]
     fn test_deserialize() {
         use rustc_serialize::json;
 
@@ -702,14 +1398,15 @@ mod tests {
 
         if cfg!(feature = "disp_hexstring") {
             let d = "\"12:34:56:AB:CD:EF\"";
-            assert_eq!(mac, json::decode(&d).unwrap());
+            assert_eq!(mac, json::decode(d).unwrap());
         } else {
             let d = "\"12-34-56-AB-CD-EF\"";
-            assert_eq!(mac, json::decode(&d).unwrap());
+            assert_eq!(mac, json::decode(d).unwrap());
         }
     }
 
     #[test]
+    #[cfg(feature = "rustc-serialize")]
     fn test_serialize_roundtrip() {
         use rustc_serialize::json;
 
@@ -797,6 +1494,24 @@ mod tests {
         assert_ne!(MacAddressFormat::HexString, MacAddressFormat::Canonical);
     }
 
+    #[test]
+    fn test_display_roundtrip() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        for &default in MacAddressFormat::ALL {
+            let mut buf = [0; 17];
+            let s = mac.write_to(&mut buf, default.parseable());
+            assert_eq!(Ok(mac), MacAddress::parse_str(s), "{:?} {}", default, s);
+        }
+    }
+
+    #[test]
+    fn test_macaddressformat_from_u8() {
+        assert_eq!(10, MacAddressFormat::ALL.len());
+        for &fmt in MacAddressFormat::ALL {
+            assert_eq!(fmt, MacAddressFormat::from_u8(fmt as u8));
+        }
+    }
+
     #[test]
     fn test_parseerror_fmt() {
         assert_eq!(
@@ -809,6 +1524,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parseerror_with_input() {
+        let err = ParseError::InvalidCharacter(16, 'g');
+        assert_eq!(Some(16), err.position());
+        assert_eq!(
+            "Invalid character; found 'g' at offset 16\n12:34:56:ab:cd:eg\n                ^",
+            err.with_input("12:34:56:ab:cd:eg").to_string()
+        );
+        let err = ParseError::TrailingCharacters(3);
+        assert_eq!(
+            "Trailing characters; found at offset 3\näb:cd\n  ^",
+            err.with_input("äb:cd").to_string()
+        );
+        let err = ParseError::InvalidLength(2);
+        assert_eq!(None, err.position());
+        assert_eq!(err.to_string(), err.with_input("12").to_string());
+        assert_eq!(
+            "Invalid byte count; Matched `7` bytes ([0, 0, 0, 0, 0, 0])",
+            ParseError::InvalidByteCount(7, [0; 6]).to_string()
+        );
+    }
+
     #[test]
     fn test_to_array() {
         let eui: Eui48 = [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF];
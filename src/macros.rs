@@ -0,0 +1,84 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Address literals validated at compile time
+
+/// Creates a `MacAddress` from a string literal, parsed at compile time
+///
+/// The literal must be written in one of the notations of `MacAddressFormat`, as accepted by a
+/// strict `MacParser`. Malformed input fails compilation. The macro can be used in constants.
+///
+/// ```
+/// use eui48::{mac, MacAddress};
+///
+/// const LLDP: MacAddress = mac!("01:80:c2:00:00:0e");
+/// assert_eq!(LLDP, MacAddress::new([0x01, 0x80, 0xc2, 0, 0, 0x0e]));
+/// assert_eq!(LLDP, mac!("0180.c200.000e"));
+/// ```
+///
+/// ```compile_fail
+/// let mac = eui48::mac!("01:80:c2:00:00:0g");
+/// ```
+#[macro_export]
+macro_rules! mac {
+    ($s:expr) => {{
+        const MAC: $crate::MacAddress = $crate::MacAddress::new($crate::__parse_literal($s));
+        MAC
+    }};
+}
+
+/// Creates an `Eui64Address` from a string literal, parsed at compile time
+///
+/// The literal must be written in one of the notations of `MacAddressFormat`, with eight bytes.
+/// Malformed input fails compilation. The macro can be used in constants.
+///
+/// ```
+/// use eui48::{eui64, Eui64Address};
+///
+/// const COORDINATOR: Eui64Address = eui64!("00:12:4b:00:01:02:03:04");
+/// assert_eq!(COORDINATOR, eui64!("0x00124b0001020304"));
+/// ```
+///
+/// ```compile_fail
+/// let eui = eui48::eui64!("00:12:4b:00:01:02:03");
+/// ```
+#[macro_export]
+macro_rules! eui64 {
+    ($s:expr) => {{
+        const EUI: $crate::Eui64Address = $crate::Eui64Address::new($crate::__parse_literal($s));
+        EUI
+    }};
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use crate::{Eui64Address, MacAddress};
+
+    #[test]
+    fn test_mac() {
+        const MAC: MacAddress = mac!("12:34:56:ab:cd:ef");
+        let expected = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(expected, MAC);
+        assert_eq!(expected, mac!("12-34-56-AB-CD-EF"));
+        assert_eq!(expected, mac!("1234.56ab.cdef"));
+        assert_eq!(expected, mac!("0x123456abcdef"));
+        assert_eq!(expected, mac!("123456abcdef"));
+    }
+
+    #[test]
+    fn test_eui64() {
+        const EUI: Eui64Address = eui64!("12:34:56:ff:fe:ab:cd:ef");
+        let expected = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(expected, EUI);
+        assert_eq!(expected, eui64!("12-34-56-FF-FE-AB-CD-EF"));
+        assert_eq!(expected, eui64!("1234.56ff.feab.cdef"));
+        assert_eq!(expected, eui64!("0x123456fffeabcdef"));
+        assert_eq!(expected, eui64!("123456fffeabcdef"));
+    }
+}
@@ -0,0 +1,495 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Address and mask matching in the notations of access lists and firewall rules
+
+use core::fmt;
+use core::str::FromStr;
+
+use super::bitwise::from_bits;
+use super::display::{as_str, encode};
+use super::{LetterCase, MacAddress, MacAddressFormat, MacPrefix, ParseError, EUI48LEN};
+
+/// The rule syntaxes a MacMatcher can be parsed from and rendered to
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MatcherDialect {
+    /// Cisco `mac access-list` address and wildcard, '0011.2200.0000 0000.00ff.ffff'
+    ///
+    /// A wildcard bit of one ignores that bit. The keywords 'any' and 'host 0011.2233.4455'
+    /// stand for the empty and the full wildcard.
+    Cisco,
+    /// ebtables address and mask, '00:11:22:00:00:00/ff:ff:ff:00:00:00'
+    ///
+    /// A mask bit of one compares that bit. An address without a mask matches exactly.
+    Ebtables,
+    /// Octets or '*' for any octet, '00:11:22:*:*:*'
+    Glob,
+}
+
+/// Matches MAC addresses against an address, comparing only the bits selected by a mask
+///
+/// Unlike a `MacPrefix`, the mask need not be contiguous. Bits of the address outside the
+/// mask are cleared when a MacMatcher is created.
+///
+/// ```
+/// use eui48::{MacAddress, MacMatcher, MatcherDialect};
+///
+/// let rule: MacMatcher = "0011.2200.0000 0000.00ff.ffff".parse().unwrap();
+/// assert!(rule.matches(&MacAddress::new([0x00, 0x11, 0x22, 0xab, 0xcd, 0xef])));
+/// assert_eq!(rule, "00:11:22:*:*:*".parse().unwrap());
+/// assert_eq!(
+///     "00:11:22:00:00:00/ff:ff:ff:00:00:00",
+///     rule.display(MatcherDialect::Ebtables).unwrap().to_string()
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MacMatcher {
+    addr: MacAddress,
+    mask: MacAddress,
+}
+
+impl MacMatcher {
+    /// Create a matcher comparing the bits of `addr` where `mask` has a one bit
+    pub const fn new(addr: MacAddress, mask: MacAddress) -> MacMatcher {
+        MacMatcher {
+            addr: from_bits(addr.to_u64() & mask.to_u64()),
+            mask,
+        }
+    }
+
+    /// Create a matcher ignoring the bits of `addr` where `wildcard` has a one bit
+    pub const fn from_wildcard(addr: MacAddress, wildcard: MacAddress) -> MacMatcher {
+        MacMatcher::new(addr, from_bits(!wildcard.to_u64()))
+    }
+
+    /// Create a matcher accepting only `addr`
+    pub const fn exact(addr: MacAddress) -> MacMatcher {
+        MacMatcher::new(addr, MacAddress::broadcast())
+    }
+
+    /// Create a matcher accepting every address
+    pub const fn any() -> MacMatcher {
+        MacMatcher::new(MacAddress::nil(), MacAddress::nil())
+    }
+
+    /// Returns the address compared against, with bits outside the mask cleared
+    pub const fn address(&self) -> MacAddress {
+        self.addr
+    }
+
+    /// Returns the mask, with a one bit for each bit compared
+    pub const fn mask(&self) -> MacAddress {
+        self.mask
+    }
+
+    /// Returns the wildcard, with a one bit for each bit ignored
+    pub const fn wildcard(&self) -> MacAddress {
+        from_bits(!self.mask.to_u64())
+    }
+
+    /// Returns true if `mac` equals the address in every bit selected by the mask
+    pub const fn matches(&self, mac: &MacAddress) -> bool {
+        mac.to_u64() & self.mask.to_u64() == self.addr.to_u64()
+    }
+
+    /// Parses a matcher in the syntax of dialect
+    pub fn parse_dialect(s: &str, dialect: MatcherDialect) -> Result<MacMatcher, ParseError> {
+        match dialect {
+            MatcherDialect::Cisco => parse_cisco(s),
+            MatcherDialect::Ebtables => parse_ebtables(s),
+            MatcherDialect::Glob => parse_glob(s),
+        }
+    }
+
+    /// Parses a matcher in any dialect, or a single address accepted by `MacAddress::parse_str`
+    ///
+    /// Input containing '*' is read as a glob, input containing '/' as ebtables, and input
+    /// containing whitespace or the keyword 'any' as Cisco.
+    pub fn parse_str(s: &str) -> Result<MacMatcher, ParseError> {
+        if s.contains('*') {
+            parse_glob(s)
+        } else if s.contains('/') {
+            parse_ebtables(s)
+        } else if s == "any" || s.contains(char::is_whitespace) {
+            parse_cisco(s)
+        } else {
+            MacAddress::parse_str(s).map(MacMatcher::exact)
+        }
+    }
+
+    /// Returns a value that displays the matcher in the syntax of dialect
+    ///
+    /// Returns None for `MatcherDialect::Glob` if the mask selects only some bits of an octet.
+    pub fn display(&self, dialect: MatcherDialect) -> Option<MacMatcherDisplay> {
+        let octet_aligned = self.mask.as_bytes().iter().all(|&b| b == 0 || b == 0xFF);
+        if dialect == MatcherDialect::Glob && !octet_aligned {
+            return None;
+        }
+        Some(MacMatcherDisplay {
+            matcher: *self,
+            dialect,
+        })
+    }
+
+    /// Renders the matcher into `buf` in the syntax of dialect
+    fn write_to<'a>(&self, buf: &'a mut [u8; 35], dialect: MatcherDialect) -> &'a str {
+        let full = self.mask == MacAddress::broadcast();
+        let mut len = 0;
+        match dialect {
+            MatcherDialect::Cisco if self.mask.is_nil() => return "any",
+            MatcherDialect::Cisco => {
+                if full {
+                    buf[..5].copy_from_slice(b"host ");
+                    len = 5;
+                }
+                let fmt = MacAddressFormat::DotNotation;
+                len += encode(
+                    self.addr.as_bytes(),
+                    &mut buf[len..],
+                    fmt,
+                    LetterCase::Lower,
+                );
+                if !full {
+                    buf[len] = b' ';
+                    len += 1;
+                    let wildcard = self.wildcard();
+                    len += encode(wildcard.as_bytes(), &mut buf[len..], fmt, LetterCase::Lower);
+                }
+            }
+            MatcherDialect::Ebtables => {
+                let fmt = MacAddressFormat::HexString;
+                len = encode(self.addr.as_bytes(), buf, fmt, LetterCase::Lower);
+                if !full {
+                    buf[len] = b'/';
+                    len += 1;
+                    len += encode(
+                        self.mask.as_bytes(),
+                        &mut buf[len..],
+                        fmt,
+                        LetterCase::Lower,
+                    );
+                }
+            }
+            MatcherDialect::Glob => {
+                let octets = self.addr.as_bytes().iter().zip(self.mask.as_bytes());
+                for (i, (&b, &m)) in octets.enumerate() {
+                    if i > 0 {
+                        buf[len] = b':';
+                        len += 1;
+                    }
+                    if m == 0 {
+                        buf[len] = b'*';
+                        len += 1;
+                    } else {
+                        len += encode(
+                            &[b],
+                            &mut buf[len..],
+                            MacAddressFormat::BareHex,
+                            LetterCase::Lower,
+                        );
+                    }
+                }
+            }
+        }
+        as_str(&buf[..len])
+    }
+}
+
+/// Parses 'any', 'host address' or 'address wildcard'
+fn parse_cisco(s: &str) -> Result<MacMatcher, ParseError> {
+    if s == "any" {
+        return Ok(MacMatcher::any());
+    }
+    let pos = s
+        .find(char::is_whitespace)
+        .ok_or(ParseError::UnknownFormat)?;
+    let rest = s[pos..].trim_start();
+    let offset = s.len() - rest.len();
+    let second = MacAddress::parse_str(rest).map_err(|err| err.offset_by(offset))?;
+    if &s[..pos] == "host" {
+        return Ok(MacMatcher::exact(second));
+    }
+    let addr = MacAddress::parse_str(&s[..pos])?;
+    Ok(MacMatcher::from_wildcard(addr, second))
+}
+
+/// Parses 'address/mask' or a single address
+fn parse_ebtables(s: &str) -> Result<MacMatcher, ParseError> {
+    match s.find('/') {
+        Some(pos) => {
+            let addr = MacAddress::parse_str(&s[..pos])?;
+            let mask =
+                MacAddress::parse_str(&s[pos + 1..]).map_err(|err| err.offset_by(pos + 1))?;
+            Ok(MacMatcher::new(addr, mask))
+        }
+        None => MacAddress::parse_str(s).map(MacMatcher::exact),
+    }
+}
+
+/// Parses six octets or '*' separated by ':' or '-'
+fn parse_glob(s: &str) -> Result<MacMatcher, ParseError> {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() {
+        return Err(ParseError::InvalidLength(0));
+    }
+    let invalid = |i: usize| {
+        let c = s[i..].chars().next().unwrap_or_default();
+        ParseError::InvalidCharacter(i, c)
+    };
+
+    let mut addr = [0; EUI48LEN];
+    let mut mask = [0; EUI48LEN];
+    let mut separator = None;
+    let mut count = 0;
+    let mut i = 0;
+    loop {
+        if bytes.get(i) == Some(&b'*') {
+            i += 1;
+        } else {
+            for _ in 0..2 {
+                let digit = match bytes.get(i) {
+                    Some(c) => (*c as char).to_digit(16).ok_or_else(|| invalid(i))?,
+                    None => return Err(ParseError::InvalidByteCount(count, addr)),
+                };
+                addr[count] = (addr[count] << 4) | digit as u8;
+                i += 1;
+            }
+            mask[count] = 0xFF;
+        }
+        count += 1;
+
+        match bytes.get(i) {
+            None => break,
+            Some(&c) if c == b':' || c == b'-' => {
+                if count == EUI48LEN {
+                    return Err(ParseError::TrailingCharacters(i));
+                }
+                if *separator.get_or_insert(c) != c {
+                    return Err(ParseError::InconsistentSeparators(i, c as char));
+                }
+                i += 1;
+            }
+            Some(_) => return Err(invalid(i)),
+        }
+    }
+    if count != EUI48LEN {
+        return Err(ParseError::InvalidByteCount(count, addr));
+    }
+    Ok(MacMatcher::new(
+        MacAddress::new(addr),
+        MacAddress::new(mask),
+    ))
+}
+
+impl From<MacAddress> for MacMatcher {
+    /// Create a matcher accepting only `mac`
+    fn from(mac: MacAddress) -> MacMatcher {
+        MacMatcher::exact(mac)
+    }
+}
+
+impl From<MacPrefix> for MacMatcher {
+    /// Create a matcher accepting the addresses of `prefix`
+    fn from(prefix: MacPrefix) -> MacMatcher {
+        MacMatcher::new(prefix.network(), prefix.mask())
+    }
+}
+
+impl FromStr for MacMatcher {
+    type Err = ParseError;
+    /// Create a MacMatcher from String, see `MacMatcher::parse_str`
+    fn from_str(s: &str) -> Result<MacMatcher, ParseError> {
+        MacMatcher::parse_str(s)
+    }
+}
+
+impl fmt::Debug for MacMatcher {
+    /// Debug format for MacMatcher is the ebtables dialect
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 35];
+        write!(
+            f,
+            "MacMatcher(\"{}\")",
+            self.write_to(&mut buf, MatcherDialect::Ebtables)
+        )
+    }
+}
+
+impl fmt::Display for MacMatcher {
+    /// Display format for MacMatcher is the ebtables dialect
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 35];
+        f.pad(self.write_to(&mut buf, MatcherDialect::Ebtables))
+    }
+}
+
+/// Displays a MacMatcher in one dialect, see `MacMatcher::display`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacMatcherDisplay {
+    matcher: MacMatcher,
+    dialect: MatcherDialect,
+}
+
+impl fmt::Display for MacMatcherDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 35];
+        f.pad(self.matcher.write_to(&mut buf, self.dialect))
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let mac = MacAddress::new([0x00, 0x11, 0x22, 0xAB, 0xCD, 0xEF]);
+        let rule = MacMatcher::new(mac, MacAddress::new([0xFF, 0xFF, 0xFF, 0, 0, 0]));
+        assert_eq!(MacAddress::new([0x00, 0x11, 0x22, 0, 0, 0]), rule.address());
+        assert_eq!(
+            MacAddress::new([0, 0, 0, 0xFF, 0xFF, 0xFF]),
+            rule.wildcard()
+        );
+        assert!(rule.matches(&mac));
+        assert!(rule.matches(&MacAddress::new([0x00, 0x11, 0x22, 0, 0, 0])));
+        assert!(!rule.matches(&MacAddress::new([0x00, 0x11, 0x23, 0, 0, 0])));
+        assert_eq!(
+            rule,
+            MacMatcher::from_wildcard(mac, MacAddress::new([0, 0, 0, 0xFF, 0xFF, 0xFF]))
+        );
+
+        // Only the locally administered bit of the first octet
+        let local = MacMatcher::new(
+            MacAddress::new([0x02, 0, 0, 0, 0, 0]),
+            MacAddress::new([0x02, 0, 0, 0, 0, 0]),
+        );
+        assert!(local.matches(&MacAddress::new([0x12, 0x34, 0x56, 0, 0, 0])));
+        assert!(!local.matches(&mac));
+
+        assert!(MacMatcher::any().matches(&mac));
+        assert!(MacMatcher::exact(mac).matches(&mac));
+        assert!(!MacMatcher::from(mac).matches(&mac.wrapping_add(1)));
+        let prefix = MacPrefix::new(mac, 24).unwrap();
+        assert_eq!(rule, MacMatcher::from(prefix));
+    }
+
+    #[test]
+    fn test_parse() {
+        use super::ParseError::*;
+
+        let rule = MacMatcher::new(
+            MacAddress::new([0x00, 0x11, 0x22, 0, 0, 0]),
+            MacAddress::new([0xFF, 0xFF, 0xFF, 0, 0, 0]),
+        );
+        let exact = MacMatcher::exact(MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]));
+        assert_eq!(Ok(rule), "0011.2200.0000 0000.00ff.ffff".parse());
+        assert_eq!(Ok(rule), "0011.2233.4455   0000.00FF.FFFF".parse());
+        assert_eq!(Ok(rule), "00:11:22:00:00:00/ff:ff:ff:00:00:00".parse());
+        assert_eq!(Ok(rule), "00:11:22:*:*:*".parse());
+        assert_eq!(Ok(rule), "00-11-22-*-*-*".parse());
+        assert_eq!(Ok(exact), "host 0011.2233.4455".parse());
+        assert_eq!(Ok(exact), "00:11:22:33:44:55".parse());
+        assert_eq!(Ok(MacMatcher::any()), "any".parse());
+        assert_eq!(Ok(MacMatcher::any()), "*:*:*:*:*:*".parse());
+        assert_eq!(
+            Ok(exact),
+            MacMatcher::parse_dialect("00:11:22:33:44:55", MatcherDialect::Ebtables)
+        );
+        assert_eq!(
+            Err(UnknownFormat),
+            MacMatcher::parse_dialect("0011.2233.4455", MatcherDialect::Cisco)
+        );
+
+        assert_eq!(
+            Err(InvalidByteCount(4, [0x00, 0x11, 0x22, 0, 0, 0])),
+            "00:11:22:*".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(InvalidByteCount(3, [0x00, 0x11, 0, 0, 0, 0])),
+            "00:11:*:".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(TrailingCharacters(11)),
+            "*:*:*:*:*:*:*".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(InconsistentSeparators(5, '-')),
+            "00:11-*:*:*:*".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(InvalidCharacter(4, 'g')),
+            "00:1g:*:*:*:*".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(InvalidCharacter(4, '0')),
+            "00:*0:*:*:*:*".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(InvalidLength(5)),
+            "00:11:22:00:00:00/ff:ff".parse::<MacMatcher>()
+        );
+        assert_eq!(
+            Err(InvalidLength(0)),
+            MacMatcher::parse_dialect("", MatcherDialect::Glob)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let rule: MacMatcher = "00:11:22:*:*:*".parse().unwrap();
+        let render = |m: &MacMatcher, d| m.display(d).map(|d| d.to_string());
+        assert_eq!(
+            Some("0011.2200.0000 0000.00ff.ffff".to_string()),
+            render(&rule, MatcherDialect::Cisco)
+        );
+        assert_eq!(
+            Some("00:11:22:00:00:00/ff:ff:ff:00:00:00".to_string()),
+            render(&rule, MatcherDialect::Ebtables)
+        );
+        assert_eq!(
+            Some("00:11:22:*:*:*".to_string()),
+            render(&rule, MatcherDialect::Glob)
+        );
+        assert_eq!("00:11:22:00:00:00/ff:ff:ff:00:00:00", rule.to_string());
+        assert_eq!(
+            "MacMatcher(\"00:11:22:00:00:00/ff:ff:ff:00:00:00\")",
+            format!("{:?}", rule)
+        );
+
+        let exact = MacMatcher::exact(MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]));
+        assert_eq!(
+            Some("host 0011.2233.4455".to_string()),
+            render(&exact, MatcherDialect::Cisco)
+        );
+        assert_eq!("00:11:22:33:44:55", exact.to_string());
+        assert_eq!(
+            Some("any".to_string()),
+            render(&MacMatcher::any(), MatcherDialect::Cisco)
+        );
+        assert_eq!(
+            Some("*:*:*:*:*:*".to_string()),
+            render(&MacMatcher::any(), MatcherDialect::Glob)
+        );
+
+        let local: MacMatcher = "02:00:00:00:00:00/02:00:00:00:00:00".parse().unwrap();
+        assert_eq!(None, render(&local, MatcherDialect::Glob));
+        assert_eq!(
+            Some("0200.0000.0000 fdff.ffff.ffff".to_string()),
+            render(&local, MatcherDialect::Cisco)
+        );
+        for dialect in [
+            MatcherDialect::Cisco,
+            MatcherDialect::Ebtables,
+            MatcherDialect::Glob,
+        ] {
+            let text = render(&rule, dialect).unwrap();
+            assert_eq!(Ok(rule), MacMatcher::parse_dialect(&text, dialect));
+        }
+    }
+}
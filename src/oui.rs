@@ -0,0 +1,316 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Organizations registered with the IEEE as owners of address blocks
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "oui-db")]
+use eui48_oui_db::{ASSIGNMENTS, TEXT, TEXT_ENDS};
+
+#[cfg(feature = "oui-db")]
+use super::bitwise::from_bits;
+#[cfg(any(test, feature = "oui-db", feature = "std"))]
+use super::MacAddress;
+use super::MacPrefix;
+
+/// Text of a Vendor
+#[derive(Clone)]
+enum Text {
+    /// A string literal
+    #[cfg(test)]
+    Static(&'static str),
+    /// An index into the text table of the eui48-oui-db crate
+    ///
+    /// Holding no pointer keeps the compile-time evaluation of the table quick.
+    #[cfg(feature = "oui-db")]
+    Builtin(u32),
+    /// A string read at runtime
+    #[cfg(feature = "std")]
+    Owned(String),
+}
+
+impl Text {
+    fn as_str(&self) -> &str {
+        match *self {
+            #[cfg(test)]
+            Text::Static(s) => s,
+            #[cfg(feature = "oui-db")]
+            Text::Builtin(index) => {
+                let index = index as usize;
+                let start = if index == 0 { 0 } else { TEXT_ENDS[index - 1] };
+                &TEXT[start as usize..TEXT_ENDS[index] as usize]
+            }
+            #[cfg(feature = "std")]
+            Text::Owned(ref s) => s,
+        }
+    }
+}
+
+impl PartialEq for Text {
+    fn eq(&self, other: &Text) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Text {}
+
+impl Hash for Text {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for Text {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// The IEEE registries assigning address blocks
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Registry {
+    /// MAC Address Block Large, a 24-bit OUI
+    MaL,
+    /// MAC Address Block Medium, a 28-bit prefix
+    MaM,
+    /// MAC Address Block Small, a 36-bit prefix
+    MaS,
+    /// Individual Address Block, a 36-bit prefix assigned before MA-S
+    Iab,
+    /// Company ID, a 24-bit prefix in the locally administered space
+    Cid,
+}
+
+impl Registry {
+    /// Returns the length of the prefixes the registry assigns
+    pub const fn prefix_len(&self) -> u8 {
+        match self {
+            Registry::MaL | Registry::Cid => 24,
+            Registry::MaM => 28,
+            Registry::MaS | Registry::Iab => 36,
+        }
+    }
+}
+
+impl fmt::Display for Registry {
+    /// Display format is the registry name used by the IEEE, such as 'MA-L'
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Registry::MaL => "MA-L",
+            Registry::MaM => "MA-M",
+            Registry::MaS => "MA-S",
+            Registry::Iab => "IAB",
+            Registry::Cid => "CID",
+        })
+    }
+}
+
+/// An organization and the address block assigned to it
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Vendor {
+    registry: Registry,
+    block: MacPrefix,
+    organization: Text,
+    address: Text,
+}
+
+impl Vendor {
+    /// Create an assignment of `block` to an organization
+    #[cfg(test)]
+    pub(crate) const fn new(
+        registry: Registry,
+        block: MacPrefix,
+        organization: &'static str,
+        address: &'static str,
+    ) -> Vendor {
+        Vendor {
+            registry,
+            block,
+            organization: Text::Static(organization),
+            address: Text::Static(address),
+        }
+    }
+
+    /// Create an assignment of `block` to an organization read at runtime
+    #[cfg(feature = "std")]
+    pub(crate) fn new_owned(
+        registry: Registry,
+        block: MacPrefix,
+        organization: String,
+        address: String,
+    ) -> Vendor {
+        Vendor {
+            registry,
+            block,
+            organization: Text::Owned(organization),
+            address: Text::Owned(address),
+        }
+    }
+
+    /// Returns the registry that assigned the block
+    pub const fn registry(&self) -> Registry {
+        self.registry
+    }
+
+    /// Returns the assigned address block
+    pub const fn block(&self) -> MacPrefix {
+        self.block
+    }
+
+    /// Returns the name of the organization
+    pub fn organization(&self) -> &str {
+        self.organization.as_str()
+    }
+
+    /// Returns the postal address of the organization, empty for private listings
+    pub fn address(&self) -> &str {
+        self.address.as_str()
+    }
+}
+
+/// The compiled-in registry, sorted by block
+#[cfg(feature = "oui-db")]
+#[allow(long_running_const_eval)]
+static OUI_DB: [Vendor; ASSIGNMENTS.len()] = expand_oui_db();
+
+/// Expands the compact table of the eui48-oui-db crate into Vendor entries
+///
+/// Evaluated at compile time, as 58,000 Vendor literals would take a minute to compile.
+#[cfg(feature = "oui-db")]
+#[allow(clippy::forget_non_drop)]
+const fn expand_oui_db() -> [Vendor; ASSIGNMENTS.len()] {
+    const EMPTY: Vendor = Vendor {
+        registry: Registry::MaL,
+        block: MacPrefix::new(MacAddress::nil(), 0).unwrap(),
+        organization: Text::Builtin(0),
+        address: Text::Builtin(0),
+    };
+    let mut table = [EMPTY; ASSIGNMENTS.len()];
+    let mut i = 0;
+    while i < ASSIGNMENTS.len() {
+        let (key, registry, organization, address) = ASSIGNMENTS[i];
+        let registry = match registry {
+            eui48_oui_db::MA_L => Registry::MaL,
+            eui48_oui_db::MA_M => Registry::MaM,
+            eui48_oui_db::MA_S => Registry::MaS,
+            eui48_oui_db::IAB => Registry::Iab,
+            _ => panic!("unknown registry"),
+        };
+        let vendor = Vendor {
+            registry,
+            block: MacPrefix::from_network(from_bits(key >> 8), key as u8),
+            organization: Text::Builtin(organization),
+            address: Text::Builtin(address),
+        };
+        // The placeholder cannot be dropped in a const fn with std, where Vendor may own text,
+        // and owns nothing anyway
+        core::mem::forget(core::mem::replace(&mut table[i], vendor));
+        i += 1;
+    }
+    table
+}
+
+/// Returns the entries of the compiled-in registry, sorted by block
+#[cfg(all(feature = "oui-db", feature = "std"))]
+pub(crate) fn builtin() -> &'static [Vendor] {
+    &OUI_DB
+}
+
+/// Returns the entry of `table` with the longest block containing `mac`
+///
+/// `table` must be sorted by block.
+#[cfg(any(test, feature = "oui-db", feature = "std"))]
+pub(crate) fn longest_match<'a>(table: &'a [Vendor], mac: &MacAddress) -> Option<&'a Vendor> {
+    [36, 28, 24].iter().find_map(|&prefix_len| {
+        let key = MacPrefix::new(*mac, prefix_len)?;
+        let i = table.binary_search_by(|v| v.block.cmp(&key)).ok()?;
+        Some(&table[i])
+    })
+}
+
+#[cfg(feature = "oui-db")]
+impl MacAddress {
+    /// Returns the organization the IEEE assigned this address to, from the compiled-in
+    /// registry
+    ///
+    /// A 28-bit or 36-bit assignment is preferred over the 24-bit block enclosing it.
+    ///
+    /// ```
+    /// use eui48::{MacAddress, Registry};
+    ///
+    /// let mac = MacAddress::new([0x00, 0x00, 0x0c, 0x12, 0x34, 0x56]);
+    /// let vendor = mac.vendor().unwrap();
+    /// assert_eq!(Registry::MaL, vendor.registry());
+    /// assert_eq!("Cisco Systems, Inc", vendor.organization());
+    /// ```
+    pub fn vendor(&self) -> Option<&'static Vendor> {
+        longest_match(&OUI_DB, self)
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefix(s: &str) -> MacPrefix {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let table = [
+            Vendor::new(Registry::MaL, prefix("00:50:c2:00:00:00/24"), "RA", ""),
+            Vendor::new(Registry::Iab, prefix("00:50:c2:12:30:00/36"), "IAB", ""),
+            Vendor::new(Registry::MaL, prefix("70:b3:d5:00:00:00/24"), "RA", ""),
+            Vendor::new(Registry::MaM, prefix("70:b3:d5:10:00:00/28"), "M", ""),
+            Vendor::new(Registry::MaS, prefix("70:b3:d5:12:30:00/36"), "S", ""),
+        ];
+        let lookup = |s: &str| longest_match(&table, &s.parse().unwrap()).map(|v| v.organization());
+        assert_eq!(Some("IAB"), lookup("00:50:c2:12:3f:ff"));
+        assert_eq!(Some("RA"), lookup("00:50:c2:12:40:00"));
+        assert_eq!(Some("S"), lookup("70:b3:d5:12:34:56"));
+        assert_eq!(Some("M"), lookup("70:b3:d5:1f:ff:ff"));
+        assert_eq!(Some("RA"), lookup("70:b3:d5:20:00:00"));
+        assert_eq!(None, lookup("70:b3:d6:00:00:00"));
+        assert_eq!(None, longest_match(&[], &MacAddress::nil()));
+    }
+
+    #[test]
+    fn test_registry() {
+        assert_eq!("MA-S", Registry::MaS.to_string());
+        assert_eq!("IAB ", format!("{:<4}", Registry::Iab));
+        assert_eq!(28, Registry::MaM.prefix_len());
+    }
+
+    #[test]
+    #[cfg(feature = "oui-db")]
+    fn test_vendor() {
+        let mac = MacAddress::new([0x00, 0x50, 0x56, 0xAB, 0xCD, 0xEF]);
+        let vendor = mac.vendor().unwrap();
+        assert_eq!("VMware, Inc.", vendor.organization());
+        assert_eq!(
+            "3401 Hillview Avenue Palo Alto CA US 94304",
+            vendor.address()
+        );
+        assert_eq!(prefix("00:50:56:00:00:00/24"), vendor.block());
+        assert!(OUI_DB.windows(2).all(|w| w[0].block() < w[1].block()));
+        assert_eq!(None, MacAddress::broadcast().vendor());
+
+        let mac = MacAddress::new([0x70, 0xB3, 0xD5, 0x12, 0x34, 0x56]);
+        assert_eq!(36, mac.vendor().unwrap().block().prefix_len());
+        let iab = MacAddress::new([0x00, 0x50, 0xC2, 0x00, 0x1A, 0xBC]);
+        assert_eq!(Registry::Iab, iab.vendor().unwrap().registry());
+        assert!(OUI_DB.len() > 50_000);
+    }
+}
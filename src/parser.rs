@@ -0,0 +1,812 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Allocation free parsing of EUI-48 and EUI-64 strings
+
+use super::{Eui48, Eui64, MacAddress, MacAddressFormat, ParseError, EUI48LEN, EUI64LEN};
+
+/// Bit in `MacParser::formats` for a notation
+const fn format_bit(fmt: MacAddressFormat) -> u16 {
+    1 << fmt as u16
+}
+
+/// Every notation in MacAddressFormat that can be told apart from the others
+const ALL_FORMATS: u16 = format_bit(MacAddressFormat::Canonical)
+    | format_bit(MacAddressFormat::HexString)
+    | format_bit(MacAddressFormat::DotNotation)
+    | format_bit(MacAddressFormat::Hexadecimal)
+    | format_bit(MacAddressFormat::BareHex)
+    | format_bit(MacAddressFormat::HyphenQuad)
+    | format_bit(MacAddressFormat::ColonHalves)
+    | format_bit(MacAddressFormat::HyphenHalves);
+
+/// A configurable MAC address parser
+///
+/// `MacAddress::parse_str` is lenient and reads anything that yields six hex bytes. A strict
+/// parser (the default) only accepts the notations of `MacAddressFormat`, written the way
+/// `MacAddress::to_string` writes them, except that hex digits may be in either case. Each
+/// notation can be disabled, and mixed separators ('12:34-56:ab:cd:ef') or single digit octets
+/// ('1:2:3:a:b:c') can be tolerated individually; such input is reported as
+/// `MacAddressFormat::Irregular`, provided each separator belongs to an accepted notation.
+///
+/// ```
+/// use eui48::{MacAddressFormat, MacParser};
+///
+/// let parser = MacParser::new().formats(&[MacAddressFormat::HexString]);
+/// assert!(parser.parse("12:34:56:ab:cd:ef").is_ok());
+/// assert!(parser.parse("12-34-56-ab-cd-ef").is_err());
+/// assert!(MacParser::new().parse("0x00:1:2:3:4:5").is_err());
+/// assert!(MacParser::lenient().parse("0x00:1:2:3:4:5").is_ok());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacParser {
+    /// Use the scanner of `MacAddress::parse_str`
+    lenient: bool,
+    /// Bit set of the accepted MacAddressFormat notations
+    formats: u16,
+    /// Accept different separators within one address
+    mixed_separators: bool,
+    /// Accept octets written with a single hex digit
+    single_digit_groups: bool,
+    /// Ignore leading and trailing whitespace
+    trim_whitespace: bool,
+}
+
+impl MacParser {
+    /// Create a strict parser accepting every notation of MacAddressFormat
+    pub const fn new() -> MacParser {
+        MacParser {
+            lenient: false,
+            formats: ALL_FORMATS,
+            mixed_separators: false,
+            single_digit_groups: false,
+            trim_whitespace: false,
+        }
+    }
+
+    /// Create a lenient parser, behaving like `MacAddress::parse_str`
+    ///
+    /// The notation, separator and octet width options only apply to strict parsing.
+    pub const fn lenient() -> MacParser {
+        MacParser {
+            lenient: true,
+            formats: ALL_FORMATS,
+            mixed_separators: true,
+            single_digit_groups: true,
+            trim_whitespace: false,
+        }
+    }
+
+    /// Select strict (true) or lenient (false) parsing
+    pub const fn strict(mut self, strict: bool) -> MacParser {
+        self.lenient = !strict;
+        self
+    }
+
+    /// Accept only the given notations
+    pub const fn formats(mut self, formats: &[MacAddressFormat]) -> MacParser {
+        self.formats = 0;
+        let mut i = 0;
+        while i < formats.len() {
+            self.formats |= format_bit(formats[i]);
+            i += 1;
+        }
+        self
+    }
+
+    /// Accept the given notation in addition to the current ones
+    pub const fn allow_format(mut self, fmt: MacAddressFormat) -> MacParser {
+        self.formats |= format_bit(fmt);
+        self
+    }
+
+    /// Reject the given notation
+    pub const fn deny_format(mut self, fmt: MacAddressFormat) -> MacParser {
+        self.formats &= !format_bit(fmt);
+        self
+    }
+
+    /// Tolerate different separators within one address, such as '12:34-56:ab:cd:ef'
+    pub const fn mixed_separators(mut self, allow: bool) -> MacParser {
+        self.mixed_separators = allow;
+        self
+    }
+
+    /// Tolerate octets written with a single hex digit, such as '1:2:3:a:b:c'
+    pub const fn single_digit_groups(mut self, allow: bool) -> MacParser {
+        self.single_digit_groups = allow;
+        self
+    }
+
+    /// Ignore leading and trailing whitespace
+    pub const fn trim_whitespace(mut self, trim: bool) -> MacParser {
+        self.trim_whitespace = trim;
+        self
+    }
+
+    /// Returns true if the notation is accepted by a strict parser
+    pub const fn is_allowed(&self, fmt: MacAddressFormat) -> bool {
+        self.formats & format_bit(fmt) != 0
+    }
+
+    /// Parses a MacAddress with the configured options
+    pub fn parse(&self, s: &str) -> Result<MacAddress, ParseError> {
+        if self.lenient {
+            let s = if self.trim_whitespace { s.trim() } else { s };
+            return parse_lenient(s);
+        }
+        self.parse_with_format(s).map(|(mac, _)| mac)
+    }
+
+    /// Parses a MacAddress with the configured options, also returning the notation used
+    ///
+    /// A lenient parser reports input that no strict parser would accept as
+    /// `MacAddressFormat::Irregular`.
+    pub fn parse_with_format(&self, s: &str) -> Result<(MacAddress, MacAddressFormat), ParseError> {
+        let (s, offset) = if self.trim_whitespace {
+            let trimmed = s.trim_start();
+            (trimmed.trim_end(), s.len() - trimmed.len())
+        } else {
+            (s, 0)
+        };
+        if self.lenient {
+            if let Ok((eui, fmt)) = parse_strict::<EUI48LEN>(s.as_bytes(), &TOLERANT) {
+                return Ok((MacAddress::new(eui), fmt));
+            }
+            return parse_lenient(s).map(|mac| (mac, MacAddressFormat::Irregular));
+        }
+        parse_strict::<EUI48LEN>(s.as_bytes(), self)
+            .map(|(eui, fmt)| (MacAddress::new(eui), fmt))
+            .map_err(|err| err.offset_by(offset))
+    }
+}
+
+/// Strict parser tolerating every irregularity, used to detect notations
+const TOLERANT: MacParser = MacParser::new()
+    .mixed_separators(true)
+    .single_digit_groups(true);
+
+impl Default for MacParser {
+    /// Create a strict parser accepting every notation of MacAddressFormat
+    fn default() -> MacParser {
+        MacParser::new()
+    }
+}
+
+/// Parses `s` the way `MacAddress::parse_str` does
+fn parse_lenient(s: &str) -> Result<MacAddress, ParseError> {
+    let mut eui: Eui48 = [0; EUI48LEN];
+
+    match s.len() {
+        11..=17 => {}
+        _ => {
+            return Err(ParseError::InvalidLength(s.len()));
+        }
+    }
+
+    let i = scan_bytes(s, &mut eui);
+    if i != EUI48LEN {
+        return Err(ParseError::InvalidByteCount(i, eui));
+    }
+
+    Ok(MacAddress::new(eui))
+}
+
+/// Parses one of the MacAddressFormat notations of an `N` byte address, returning the notation
+/// that was found
+///
+/// Mixed separators and single digit octets are only accepted if `parser` tolerates them, and
+/// are returned as `MacAddressFormat::Irregular`. Each separator used must still belong to a
+/// notation accepted by `parser`, or `ParseError::DisallowedFormat` is returned.
+pub(crate) const fn parse_strict<const N: usize>(
+    bytes: &[u8],
+    parser: &MacParser,
+) -> Result<([u8; N], MacAddressFormat), ParseError> {
+    let mut eui = [0; N];
+
+    if bytes.is_empty() {
+        return Err(length_error::<N>(0));
+    }
+
+    if bytes.len() > 2 && bytes[0] == b'0' && bytes[1] == b'x' {
+        let mut i = 2;
+        while i < bytes.len() && hex_value(bytes[i]).is_some() {
+            i += 1;
+        }
+        if i < bytes.len() && i == 2 + 2 * N {
+            return Err(ParseError::TrailingCharacters(i));
+        }
+        if i < bytes.len() {
+            return Err(ParseError::InvalidCharacter(i, char_at(bytes, i)));
+        }
+        if i != 2 + 2 * N {
+            return Err(ParseError::InvalidGroupWidth(2, i - 2));
+        }
+        if !parser.is_allowed(MacAddressFormat::Hexadecimal) {
+            return Err(ParseError::DisallowedFormat(MacAddressFormat::Hexadecimal));
+        }
+        decode_nibbles(bytes, 2, &mut eui);
+        return Ok((eui, MacAddressFormat::Hexadecimal));
+    }
+
+    // Split the input in groups of hex digits between separators
+    let single_digit = parser.single_digit_groups;
+    let mut groups = 0;
+    let mut min_width = usize::MAX;
+    let mut max_width = 0;
+    // First offset of each of ':', '-' and '.', or 0 if unused
+    let mut separators = [0; 3];
+    let mut mixed = false;
+    let mut i = 0;
+    loop {
+        let start = i;
+        while i < bytes.len() && hex_value(bytes[i]).is_some() {
+            i += 1;
+        }
+        let width = i - start;
+        if width == 0 {
+            return Err(if i == bytes.len() {
+                if is_complete::<N>(groups, min_width, max_width, single_digit) {
+                    ParseError::TrailingCharacters(i - 1)
+                } else {
+                    ParseError::InvalidSeparator(i - 1, char_at(bytes, i - 1))
+                }
+            } else if is_separator(bytes[i]) {
+                ParseError::InvalidSeparator(i, char_at(bytes, i))
+            } else {
+                ParseError::InvalidCharacter(i, char_at(bytes, i))
+            });
+        }
+        groups += 1;
+        if width < min_width {
+            min_width = width;
+        }
+        if width > max_width {
+            max_width = width;
+        }
+        if i == bytes.len() {
+            break;
+        }
+        if !is_separator(bytes[i]) {
+            if is_complete::<N>(groups, min_width, max_width, single_digit) {
+                return Err(ParseError::TrailingCharacters(i));
+            }
+            return Err(ParseError::InvalidCharacter(i, char_at(bytes, i)));
+        }
+        let kind = separator_index(bytes[i]);
+        if separators[kind] == 0 {
+            if separators[0] + separators[1] + separators[2] != 0 {
+                if !parser.mixed_separators {
+                    return Err(ParseError::InconsistentSeparators(i, char_at(bytes, i)));
+                }
+                mixed = true;
+            }
+            separators[kind] = i;
+        }
+        i += 1;
+    }
+
+    // The number of groups selects the layout, which then fixes the width of every group
+    let octets = max_width <= 2;
+    let width = if octets && groups != N {
+        decode_octets(bytes, &mut eui);
+        return Err(byte_count_error(groups, eui));
+    } else if octets {
+        2
+    } else if groups == 1 {
+        2 * N
+    } else if groups == N / 2 {
+        4
+    } else if groups == 2 {
+        N
+    } else {
+        return Err(ParseError::UnknownFormat);
+    };
+    if let Some((pos, found)) = find_group_width(bytes, width, single_digit) {
+        return Err(ParseError::InvalidGroupWidth(pos, found));
+    }
+
+    if octets {
+        decode_octets(bytes, &mut eui);
+    } else {
+        decode_nibbles(bytes, 0, &mut eui);
+    }
+
+    // Every separator used must belong to an accepted notation of the layout
+    let mut fmt = MacAddressFormat::BareHex;
+    if separators[0] + separators[1] + separators[2] == 0 && !parser.is_allowed(fmt) {
+        return Err(ParseError::DisallowedFormat(fmt));
+    }
+    let mut kind = 0;
+    while kind < separators.len() {
+        let pos = separators[kind];
+        if pos != 0 {
+            fmt = match (bytes[pos], width) {
+                (b'-', 2) => MacAddressFormat::Canonical,
+                (b':', 2) => MacAddressFormat::HexString,
+                (b'.', 4) => MacAddressFormat::DotNotation,
+                (b'-', 4) => MacAddressFormat::HyphenQuad,
+                (b':', w) if w == N => MacAddressFormat::ColonHalves,
+                (b'-', w) if w == N => MacAddressFormat::HyphenHalves,
+                _ => return Err(ParseError::InvalidSeparator(pos, char_at(bytes, pos))),
+            };
+            if !parser.is_allowed(fmt) {
+                return Err(ParseError::DisallowedFormat(fmt));
+            }
+        }
+        kind += 1;
+    }
+    if mixed || min_width == 1 {
+        return Ok((eui, MacAddressFormat::Irregular));
+    }
+    Ok((eui, fmt))
+}
+
+/// Index of a separator in the table of first offsets kept by `parse_strict`
+const fn separator_index(c: u8) -> usize {
+    match c {
+        b':' => 0,
+        b'-' => 1,
+        _ => 2,
+    }
+}
+
+/// Returns true for the separators between groups of hex digits
+const fn is_separator(c: u8) -> bool {
+    matches!(c, b':' | b'-' | b'.')
+}
+
+/// Returns true if the groups read so far form a complete `N` byte address
+const fn is_complete<const N: usize>(
+    groups: usize,
+    min_width: usize,
+    max_width: usize,
+    single_digit: bool,
+) -> bool {
+    if groups == N {
+        max_width <= 2 && (min_width == 2 || single_digit)
+    } else if groups == N / 2 {
+        min_width == 4 && max_width == 4
+    } else if groups == 2 {
+        min_width == N && max_width == N
+    } else {
+        groups == 1 && min_width == 2 * N
+    }
+}
+
+/// Returns the length error for an `N` byte address
+const fn length_error<const N: usize>(len: usize) -> ParseError {
+    if N == EUI64LEN {
+        ParseError::InvalidEui64Length(len)
+    } else {
+        ParseError::InvalidLength(len)
+    }
+}
+
+/// Returns the byte count error for an `N` byte address
+const fn byte_count_error<const N: usize>(groups: usize, eui: [u8; N]) -> ParseError {
+    if N == EUI64LEN {
+        let mut found: Eui64 = [0; EUI64LEN];
+        let mut i = 0;
+        while i < EUI64LEN {
+            found[i] = eui[i];
+            i += 1;
+        }
+        ParseError::InvalidEui64ByteCount(groups, found)
+    } else {
+        let mut found: Eui48 = [0; EUI48LEN];
+        let mut i = 0;
+        while i < EUI48LEN && i < N {
+            found[i] = eui[i];
+            i += 1;
+        }
+        ParseError::InvalidByteCount(groups, found)
+    }
+}
+
+/// Returns the offset and width of the first group of hex digits with a width other than
+/// `width`, tolerating single digits for octets if `single_digit` is set
+const fn find_group_width(
+    bytes: &[u8],
+    width: usize,
+    single_digit: bool,
+) -> Option<(usize, usize)> {
+    let mut start = 0;
+    let mut i = 0;
+    while i <= bytes.len() {
+        if i == bytes.len() || hex_value(bytes[i]).is_none() {
+            let found = i - start;
+            if found != width && !(found == 1 && width == 2 && single_digit) {
+                return Some((start, found));
+            }
+            start = i + 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Decodes one octet per group of hex digits, ignoring groups beyond the length of `eui`
+const fn decode_octets(bytes: &[u8], eui: &mut [u8]) {
+    let mut group = 0;
+    let mut i = 0;
+    while i < bytes.len() && group < eui.len() {
+        match hex_value(bytes[i]) {
+            Some(v) => eui[group] = eui[group] << 4 | v,
+            None => group += 1,
+        }
+        i += 1;
+    }
+}
+
+/// Decodes the hex digits of `bytes` from offset `start` as one stream of nibbles, skipping
+/// separators
+const fn decode_nibbles(bytes: &[u8], start: usize, eui: &mut [u8]) {
+    let mut nibble = 0;
+    let mut i = start;
+    while i < bytes.len() && nibble < 2 * eui.len() {
+        if let Some(v) = hex_value(bytes[i]) {
+            eui[nibble / 2] |= v << (4 * (1 - nibble % 2));
+            nibble += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Returns the character starting at byte offset `i` of the UTF-8 encoded `bytes`
+const fn char_at(bytes: &[u8], i: usize) -> char {
+    let first = bytes[i] as u32;
+    let (len, mut c) = match first {
+        0x00..=0x7F => (1, first),
+        0xC0..=0xDF => (2, first & 0x1F),
+        0xE0..=0xEF => (3, first & 0x0F),
+        _ => (4, first & 0x07),
+    };
+    let mut k = 1;
+    while k < len && i + k < bytes.len() {
+        c = c << 6 | (bytes[i + k] as u32 & 0x3F);
+        k += 1;
+    }
+    match char::from_u32(c) {
+        Some(c) => c,
+        None => char::REPLACEMENT_CHARACTER,
+    }
+}
+
+/// Parses an address literal for the `mac!` and `eui64!` macros, failing const evaluation with
+/// the reason on malformed input
+#[doc(hidden)]
+pub const fn parse_literal<const N: usize>(s: &str) -> [u8; N] {
+    match parse_strict::<N>(s.as_bytes(), &MacParser::new()) {
+        Ok((eui, _)) => eui,
+        Err(ParseError::InvalidCharacter(..)) => {
+            panic!("invalid address literal: invalid character")
+        }
+        Err(ParseError::InvalidSeparator(..)) => {
+            panic!("invalid address literal: invalid separator")
+        }
+        Err(ParseError::InconsistentSeparators(..)) => {
+            panic!("invalid address literal: inconsistent separators")
+        }
+        Err(ParseError::InvalidGroupWidth(..)) => {
+            panic!("invalid address literal: wrong number of hex digits in a group")
+        }
+        Err(ParseError::TrailingCharacters(..)) => {
+            panic!("invalid address literal: trailing characters")
+        }
+        Err(ParseError::InvalidByteCount(..)) | Err(ParseError::InvalidEui64ByteCount(..)) => {
+            panic!("invalid address literal: wrong number of bytes")
+        }
+        Err(_) => panic!("invalid address literal: not a supported notation"),
+    }
+}
+
+/// Returns the value of an ASCII hex digit
+const fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Scans `s` for hex byte groups, filling `eui` and returning the number of groups found
+///
+/// A group is one or two hex digits, optionally preceded by `0x` and followed by one of the
+/// `:`, `.` or `-` delimiters. Anything between groups is skipped, which keeps the lenient
+/// behaviour of the original `(0x)?([0-9a-fA-F]{1,2})[:.-]?` regex. Groups beyond the length
+/// of `eui` are counted but not stored, so callers can report how many bytes the input really
+/// contained.
+pub(crate) fn scan_bytes(s: &str, eui: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    let mut count = 0;
+
+    while pos < bytes.len() {
+        let mut start = pos;
+        // A 0x prefix only belongs to the group when a hex digit follows it
+        if bytes[pos] == b'0'
+            && pos + 2 < bytes.len()
+            && bytes[pos + 1] == b'x'
+            && hex_value(bytes[pos + 2]).is_some()
+        {
+            start = pos + 2;
+        }
+
+        let mut value = match hex_value(bytes[start]) {
+            Some(v) => v,
+            None => {
+                pos += 1;
+                continue;
+            }
+        };
+        let mut end = start + 1;
+        if end < bytes.len() {
+            if let Some(v) = hex_value(bytes[end]) {
+                value = value << 4 | v;
+                end += 1;
+            }
+        }
+        if end < bytes.len() && matches!(bytes[end], b':' | b'.' | b'-') {
+            end += 1;
+        }
+
+        // Fill the array and keep counting for InvalidByteCount
+        if count < eui.len() {
+            eui[count] = value;
+        }
+        count += 1;
+        pos = end;
+    }
+    count
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::{scan_bytes, MacAddressFormat, MacParser, ParseError};
+    use crate::MacAddress;
+
+    #[test]
+    fn test_scan_bytes() {
+        let mut eui = [0; 6];
+        assert_eq!(6, scan_bytes("12:34:56:ab:cd:ef", &mut eui));
+        assert_eq!([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF], eui);
+
+        let mut eui = [0; 6];
+        assert_eq!(6, scan_bytes("1:2:3:a:b:c", &mut eui));
+        assert_eq!([0x01, 0x02, 0x03, 0x0A, 0x0B, 0x0C], eui);
+
+        let mut eui = [0; 6];
+        assert_eq!(5, scan_bytes("0x0x0x0x0x0x0x0x0x0x", &mut eui));
+        assert_eq!([0; 6], eui);
+
+        let mut eui = [0; 6];
+        assert_eq!(1, scan_bytes("0x", &mut eui));
+        assert_eq!(0, scan_bytes("xx--::..", &mut eui));
+        assert_eq!(0, scan_bytes("", &mut eui));
+    }
+
+    #[test]
+    fn test_scan_bytes_non_ascii() {
+        let mut eui = [0; 6];
+        assert_eq!(6, scan_bytes("12é34ü56:ab:cd:ef", &mut eui));
+        assert_eq!([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF], eui);
+    }
+
+    #[test]
+    fn test_strict() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let parser = MacParser::new();
+        assert_eq!(Ok(mac), parser.parse("12-34-56-AB-CD-EF"));
+        assert_eq!(Ok(mac), parser.parse("12:34:56:ab:cd:ef"));
+        assert_eq!(Ok(mac), parser.parse("1234.56ab.cdef"));
+        assert_eq!(Ok(mac), parser.parse("0x123456abcdef"));
+        assert_eq!(Ok(mac), parser.parse("123456ABCDEF"));
+        assert_eq!(
+            Ok((mac, MacAddressFormat::DotNotation)),
+            parser.parse_with_format("1234.56AB.CDEF")
+        );
+        assert_eq!(
+            Ok((mac, MacAddressFormat::HyphenQuad)),
+            parser.parse_with_format("1234-56ab-cdef")
+        );
+        assert_eq!(
+            Ok((mac, MacAddressFormat::ColonHalves)),
+            parser.parse_with_format("123456:abcdef")
+        );
+        assert_eq!(
+            Ok((mac, MacAddressFormat::HyphenHalves)),
+            parser.parse_with_format("123456-ABCDEF")
+        );
+
+        use super::ParseError::*;
+        assert_eq!(Err(InvalidLength(0)), parser.parse(""));
+        assert_eq!(
+            Err(InvalidCharacter(4, ':')),
+            parser.parse("0x12:34:56:ab:cd:ef")
+        );
+        assert_eq!(
+            Err(InvalidCharacter(13, 'g')),
+            parser.parse("0x123456abcdeg")
+        );
+        assert_eq!(
+            Err(InvalidCharacter(16, 'g')),
+            parser.parse("12:34:56:ab:cd:eg")
+        );
+        assert_eq!(
+            Err(InvalidCharacter(0, ' ')),
+            parser.parse(" 12:34:56:ab:cd:ef")
+        );
+        assert_eq!(
+            Err(InvalidCharacter(2, 'é')),
+            parser.parse("12é34:56:ab:cd:ef")
+        );
+        assert_eq!(
+            Err(InvalidSeparator(15, ':')),
+            parser.parse("12:34:56:ab:cd::ef")
+        );
+        assert_eq!(
+            Err(InvalidSeparator(14, ':')),
+            parser.parse("12:34:56:ab:cd:")
+        );
+        assert_eq!(
+            Err(InvalidSeparator(2, '.')),
+            parser.parse("12.34.56.ab.cd.ef")
+        );
+        assert_eq!(
+            Err(InvalidSeparator(4, ':')),
+            parser.parse("1234:56ab:cdef")
+        );
+        assert_eq!(
+            Err(InconsistentSeparators(5, '-')),
+            parser.parse("12:34-56:ab:cd:ef")
+        );
+        assert_eq!(Err(InvalidGroupWidth(0, 1)), parser.parse("1:2:3:a:b:c"));
+        assert_eq!(
+            Err(InvalidGroupWidth(15, 1)),
+            parser.parse("12:34:56:ab:cd:e")
+        );
+        assert_eq!(Err(InvalidGroupWidth(0, 3)), parser.parse("123.456ab.cdef"));
+        assert_eq!(Err(InvalidGroupWidth(0, 5)), parser.parse("12345"));
+        assert_eq!(Err(InvalidGroupWidth(2, 11)), parser.parse("0x123456abcde"));
+        assert_eq!(
+            Err(InvalidGroupWidth(2, 14)),
+            parser.parse("0x123456abcdef01")
+        );
+        assert_eq!(
+            Err(TrailingCharacters(17)),
+            parser.parse("12:34:56:ab:cd:ef!")
+        );
+        assert_eq!(
+            Err(TrailingCharacters(17)),
+            parser.parse("12:34:56:ab:cd:ef:")
+        );
+        assert_eq!(Err(TrailingCharacters(14)), parser.parse("0x123456abcdef "));
+        assert_eq!(Err(UnknownFormat), parser.parse("1234.5678.9abc.def0"));
+        assert_eq!(Err(InvalidGroupWidth(0, 4)), parser.parse("1234.5678"));
+        assert_eq!(Err(InvalidSeparator(6, '.')), parser.parse("123456.abcdef"));
+        assert_eq!(
+            Err(InvalidCharacter(18, 'g')),
+            parser.trim_whitespace(true).parse("  12:34:56:ab:cd:eg ")
+        );
+        assert_eq!(
+            Err(InvalidByteCount(5, [0x12, 0x34, 0x56, 0xAB, 0xCD, 0])),
+            parser.parse("12:34:56:ab:cd")
+        );
+        assert_eq!(
+            Err(InvalidByteCount(7, [0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF])),
+            parser.parse("12:34:56:ab:cd:ef:01")
+        );
+    }
+
+    #[test]
+    fn test_strict_options() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        let parser = MacParser::new().formats(&[MacAddressFormat::HexString]);
+        assert_eq!(Ok(mac), parser.parse("12:34:56:ab:cd:ef"));
+        assert_eq!(
+            Err(ParseError::DisallowedFormat(MacAddressFormat::Canonical)),
+            parser.parse("12-34-56-ab-cd-ef")
+        );
+        let parser = parser.allow_format(MacAddressFormat::Canonical);
+        assert_eq!(Ok(mac), parser.parse("12-34-56-ab-cd-ef"));
+        let parser = parser.deny_format(MacAddressFormat::HexString);
+        assert!(parser.parse("12:34:56:ab:cd:ef").is_err());
+        assert!(!parser.is_allowed(MacAddressFormat::DotNotation));
+
+        let parser = MacParser::new().mixed_separators(true);
+        assert_eq!(Ok(mac), parser.parse("12:34-56:ab-cd:ef"));
+        assert_eq!(
+            Ok((mac, MacAddressFormat::Irregular)),
+            parser
+                .formats(&[MacAddressFormat::Canonical, MacAddressFormat::HexString])
+                .parse_with_format("12:34-56:ab-cd:ef")
+        );
+        assert_eq!(
+            Err(ParseError::DisallowedFormat(MacAddressFormat::HexString)),
+            parser
+                .formats(&[MacAddressFormat::DotNotation])
+                .parse("12:34-56:ab:cd:ef")
+        );
+        assert_eq!(
+            Err(ParseError::DisallowedFormat(MacAddressFormat::HyphenQuad)),
+            parser
+                .deny_format(MacAddressFormat::HyphenQuad)
+                .parse("1234.56ab-cdef")
+        );
+        assert_eq!(Ok(mac), parser.parse("1234.56ab-cdef"));
+        assert_eq!(
+            Err(ParseError::InvalidSeparator(11, '.')),
+            parser.parse("12:34-56:ab.cd:ef")
+        );
+        assert!(parser.parse("12.34:56:ab:cd:ef").is_err());
+        assert!(parser.parse("12.34.56.ab.cd.ef").is_err());
+
+        let parser = MacParser::new().single_digit_groups(true);
+        assert_eq!(
+            Ok(MacAddress::new([1, 2, 3, 0xA, 0xB, 0xC])),
+            parser.parse("1:2:3:a:b:c")
+        );
+        assert!(parser.parse("1.2.3.a.b.c").is_err());
+        assert!(parser.parse("123.456.abc").is_err());
+        assert!(parser.parse("1:2-3:a:b:c").is_err());
+        assert_eq!(
+            Ok(MacAddressFormat::Irregular),
+            parser.parse_with_format("1:2:3:a:b:c").map(|(_, fmt)| fmt)
+        );
+        assert_eq!(
+            Err(ParseError::DisallowedFormat(MacAddressFormat::HexString)),
+            parser
+                .formats(&[MacAddressFormat::Canonical])
+                .parse("1:2:3:a:b:c")
+        );
+        assert_eq!(
+            Err(ParseError::DisallowedFormat(MacAddressFormat::Hexadecimal)),
+            MacParser::new()
+                .deny_format(MacAddressFormat::Hexadecimal)
+                .parse("0x123456abcdef")
+        );
+        assert_eq!(
+            Err(ParseError::DisallowedFormat(MacAddressFormat::BareHex)),
+            MacParser::new()
+                .deny_format(MacAddressFormat::BareHex)
+                .parse("123456abcdef")
+        );
+
+        let parser = MacParser::new().trim_whitespace(true);
+        assert_eq!(Ok(mac), parser.parse(" \t12:34:56:ab:cd:ef\n"));
+    }
+
+    #[test]
+    fn test_lenient() {
+        let parser = MacParser::lenient();
+        assert_eq!(
+            Ok(MacAddress::new([0, 1, 2, 3, 4, 5])),
+            parser.parse("0x00:1:2:3:4:5")
+        );
+        assert_eq!(
+            MacAddress::parse_str("!0x00000000000"),
+            parser.parse("!0x00000000000")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidLength(19)),
+            parser.parse("  12:34:56:ab:cd:ef")
+        );
+        assert!(parser
+            .trim_whitespace(true)
+            .parse("  12:34:56:ab:cd:ef")
+            .is_ok());
+        assert!(parser.strict(true).parse("0x00:1:2:3:4:5").is_err());
+        assert!(MacParser::new()
+            .strict(false)
+            .parse("0x00:1:2:3:4:5")
+            .is_ok());
+    }
+}
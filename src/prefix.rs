@@ -0,0 +1,482 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Blocks of MAC addresses sharing a prefix, written as '00:11:22:00:00:00/24'
+
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "rustc-serialize")]
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::display::{as_str, encode};
+use super::{LetterCase, MacAddress, MacAddressFormat, MacRange, ParseError, EUI48LEN};
+
+/// Number of bits in a MacAddress
+const BITS: u8 = 8 * EUI48LEN as u8;
+
+/// A block of MAC addresses sharing their first `prefix_len` bits
+///
+/// The address is always the first address of the block; bits beyond the prefix are cleared
+/// when a MacPrefix is created. A MacPrefix is written as the address and the prefix length
+/// separated by '/', and can also be parsed from an address and a mask, as in
+/// '00:11:22:00:00:00/ff:ff:ff:00:00:00' or '0011.2200.0000 ffff.ff00.0000'.
+///
+/// ```
+/// use eui48::{MacAddress, MacPrefix};
+///
+/// let block: MacPrefix = "00:11:22:00:00:00/24".parse().unwrap();
+/// assert!(block.contains(&MacAddress::new([0x00, 0x11, 0x22, 0xab, 0xcd, 0xef])));
+/// assert_eq!(1 << 24, block.size());
+/// assert_eq!(block, "0011.2200.0000 ffff.ff00.0000".parse().unwrap());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MacPrefix {
+    network: MacAddress,
+    prefix_len: u8,
+}
+
+impl MacPrefix {
+    /// Create the prefix of the first `prefix_len` bits of `addr`
+    ///
+    /// Returns None if `prefix_len` is greater than 48.
+    pub const fn new(addr: MacAddress, prefix_len: u8) -> Option<MacPrefix> {
+        if prefix_len > BITS {
+            return None;
+        }
+        Some(MacPrefix {
+            network: addr.mask(prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// Create a prefix from a network already masked to `prefix_len` bits, which is not checked
+    #[cfg(feature = "oui-db")]
+    pub(crate) const fn from_network(network: MacAddress, prefix_len: u8) -> MacPrefix {
+        MacPrefix {
+            network,
+            prefix_len,
+        }
+    }
+
+    /// Create the prefix selected by a mask like ff-ff-ff-00-00-00
+    ///
+    /// Returns None if the one bits of `mask` are not contiguous from the first bit.
+    pub const fn from_mask(addr: MacAddress, mask: MacAddress) -> Option<MacPrefix> {
+        let prefix_len = mask.leading_ones() as u8;
+        if mask.to_u64() != MacAddress::broadcast().mask(prefix_len).to_u64() {
+            return None;
+        }
+        MacPrefix::new(addr, prefix_len)
+    }
+
+    /// Parses a prefix written as 'address/length', 'address/mask' or 'address mask'
+    ///
+    /// The address and the mask are parsed like `MacAddress::parse_str`.
+    pub fn parse_str(s: &str) -> Result<MacPrefix, ParseError> {
+        let (pos, delim) = s
+            .char_indices()
+            .find(|&(_, c)| c == '/' || c.is_whitespace())
+            .ok_or(ParseError::UnknownFormat)?;
+        let addr = MacAddress::parse_str(&s[..pos])?;
+        let rest = s[pos + delim.len_utf8()..].trim_start();
+        let offset = s.len() - rest.len();
+        if !rest.is_empty() && rest.bytes().all(|c| c.is_ascii_digit()) {
+            let prefix_len = rest.parse().unwrap_or(usize::MAX);
+            if prefix_len > usize::from(BITS) || delim != '/' {
+                return Err(ParseError::InvalidPrefixLength(prefix_len));
+            }
+            return Ok(MacPrefix {
+                network: addr.mask(prefix_len as u8),
+                prefix_len: prefix_len as u8,
+            });
+        }
+        let mask = MacAddress::parse_str(rest).map_err(|err| err.offset_by(offset))?;
+        MacPrefix::from_mask(addr, mask).ok_or(ParseError::InvalidMask(mask))
+    }
+
+    /// Returns the first address of the block
+    pub const fn network(&self) -> MacAddress {
+        self.network
+    }
+
+    /// Returns the number of leading bits shared by the addresses of the block
+    pub const fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns the mask of the prefix, such as ff-ff-ff-00-00-00 for a /24
+    pub const fn mask(&self) -> MacAddress {
+        MacAddress::broadcast().mask(self.prefix_len)
+    }
+
+    /// Returns the last address of the block
+    pub const fn last(&self) -> MacAddress {
+        match self.network.checked_add(self.size() - 1) {
+            Some(last) => last,
+            None => MacAddress::broadcast(),
+        }
+    }
+
+    /// Returns the number of addresses in the block
+    pub const fn size(&self) -> u64 {
+        1 << (BITS - self.prefix_len)
+    }
+
+    /// Returns true if `mac` belongs to the block
+    pub const fn contains(&self, mac: &MacAddress) -> bool {
+        mac.mask(self.prefix_len).to_u64() == self.network.to_u64()
+    }
+
+    /// Returns an iterator over the addresses of the block
+    pub const fn iter(&self) -> MacRange {
+        MacRange::new(self.network, self.last())
+    }
+
+    /// Returns the two halves of the block, or None for a single address
+    pub const fn split(&self) -> Option<(MacPrefix, MacPrefix)> {
+        if self.prefix_len == BITS {
+            return None;
+        }
+        let prefix_len = self.prefix_len + 1;
+        let lower = MacPrefix {
+            network: self.network,
+            prefix_len,
+        };
+        let upper = MacPrefix {
+            network: self.network.wrapping_add(self.size() / 2),
+            prefix_len,
+        };
+        Some((lower, upper))
+    }
+
+    /// Returns the block one bit shorter containing this one, or None for the /0 block
+    pub const fn supernet(&self) -> Option<MacPrefix> {
+        if self.prefix_len == 0 {
+            return None;
+        }
+        let prefix_len = self.prefix_len - 1;
+        Some(MacPrefix {
+            network: self.network.mask(prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// Renders the prefix into `buf` in the format selected by fmt
+    fn write_to<'a>(&self, buf: &'a mut [u8; 20], fmt: MacAddressFormat) -> &'a str {
+        let mut len = encode(self.network.as_bytes(), buf, fmt, LetterCase::Lower);
+        buf[len] = b'/';
+        len += 1;
+        if self.prefix_len >= 10 {
+            buf[len] = b'0' + self.prefix_len / 10;
+            len += 1;
+        }
+        buf[len] = b'0' + self.prefix_len % 10;
+        as_str(&buf[..len + 1])
+    }
+}
+
+impl From<MacAddress> for MacPrefix {
+    /// Create the /48 prefix holding only `mac`
+    fn from(mac: MacAddress) -> MacPrefix {
+        MacPrefix {
+            network: mac,
+            prefix_len: BITS,
+        }
+    }
+}
+
+impl IntoIterator for MacPrefix {
+    type Item = MacAddress;
+    type IntoIter = MacRange;
+
+    fn into_iter(self) -> MacRange {
+        self.iter()
+    }
+}
+
+impl FromStr for MacPrefix {
+    type Err = ParseError;
+    /// Create a MacPrefix from String, see `MacPrefix::parse_str`
+    fn from_str(s: &str) -> Result<MacPrefix, ParseError> {
+        MacPrefix::parse_str(s)
+    }
+}
+
+impl fmt::Debug for MacPrefix {
+    /// Debug format for MacPrefix is HexString notation
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0; 20];
+        write!(
+            f,
+            "MacPrefix(\"{}\")",
+            self.write_to(&mut buf, MacAddressFormat::HexString)
+        )
+    }
+}
+
+impl fmt::Display for MacPrefix {
+    /// Display format follows MacAddress for the address, followed by '/' and the length
+    ///
+    /// A BitReversed default is written as HexString, so the output can be parsed back.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fmt = if f.alternate() {
+            MacAddressFormat::HexString
+        } else {
+            MacAddress::get_display_format().parseable()
+        };
+        let mut buf = [0; 20];
+        f.pad(self.write_to(&mut buf, fmt))
+    }
+}
+
+#[cfg(feature = "rustc-serialize")]
+impl Encodable for MacPrefix {
+    /// Encode a MacPrefix using the default format
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_str(&format!("{}", self))
+    }
+}
+
+#[cfg(feature = "rustc-serialize")]
+impl Decodable for MacPrefix {
+    /// Decode a MacPrefix from a string
+    fn decode<D: Decoder>(d: &mut D) -> Result<MacPrefix, D::Error> {
+        let string = d.read_str()?;
+        string.parse().map_err(|err| d.error(&format!("{}", err)))
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "serde_bytes")))]
+impl Serialize for MacPrefix {
+    /// Serialize a MacPrefix in the default format using the serde crate
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde_bytes")]
+impl Serialize for MacPrefix {
+    /// Serialize a MacPrefix as the raw address bytes followed by the prefix length
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = [0; EUI48LEN + 1];
+        bytes[..EUI48LEN].copy_from_slice(self.network.as_bytes());
+        bytes[EUI48LEN] = self.prefix_len;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "serde_bytes")))]
+impl<'de> Deserialize<'de> for MacPrefix {
+    /// Deserialize a MacPrefix from a string using the serde crate
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MacPrefixVisitor;
+        impl<'de> de::Visitor<'de> for MacPrefixVisitor {
+            type Value = MacPrefix;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a string representation of a MAC address prefix")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_str(MacPrefixVisitor)
+    }
+}
+
+#[cfg(feature = "serde_bytes")]
+impl<'de> Deserialize<'de> for MacPrefix {
+    /// Deserialize a MacPrefix from raw address bytes followed by the prefix length
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MacPrefixVisitor;
+        impl<'de> de::Visitor<'de> for MacPrefixVisitor {
+            type Value = MacPrefix;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "7-element byte array")
+            }
+
+            fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+                if value.len() != EUI48LEN + 1 {
+                    return Err(E::invalid_length(value.len(), &self));
+                }
+                let mac = MacAddress::from_bytes(&value[..EUI48LEN]).map_err(E::custom)?;
+                let prefix_len = value[EUI48LEN];
+                MacPrefix::new(mac, prefix_len).ok_or_else(|| {
+                    E::custom(ParseError::InvalidPrefixLength(usize::from(prefix_len)))
+                })
+            }
+        }
+        deserializer.deserialize_bytes(MacPrefixVisitor)
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let mac = MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        let prefix = MacPrefix::new(mac, 24).unwrap();
+        assert_eq!(
+            MacAddress::new([0x00, 0x11, 0x22, 0, 0, 0]),
+            prefix.network()
+        );
+        assert_eq!(24, prefix.prefix_len());
+        assert_eq!(MacAddress::new([0xFF, 0xFF, 0xFF, 0, 0, 0]), prefix.mask());
+        assert_eq!(
+            MacAddress::new([0x00, 0x11, 0x22, 0xFF, 0xFF, 0xFF]),
+            prefix.last()
+        );
+        assert_eq!(1 << 24, prefix.size());
+        assert!(prefix.contains(&mac));
+        assert!(!prefix.contains(&MacAddress::new([0x00, 0x11, 0x23, 0, 0, 0])));
+
+        assert_eq!(None, MacPrefix::new(mac, 49));
+        assert_eq!(
+            Some(prefix),
+            MacPrefix::from_mask(mac, MacAddress::new([0xFF, 0xFF, 0xFF, 0, 0, 0]))
+        );
+        let mask = MacAddress::new([0xFF, 0x00, 0xFF, 0, 0, 0]);
+        assert_eq!(None, MacPrefix::from_mask(mac, mask));
+
+        let all = MacPrefix::new(mac, 0).unwrap();
+        assert_eq!(MacAddress::nil(), all.network());
+        assert_eq!(MacAddress::broadcast(), all.last());
+        assert_eq!(1 << 48, all.size());
+        let single = MacPrefix::from(mac);
+        assert_eq!(mac, single.last());
+        assert_eq!(vec![mac], single.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_split() {
+        let prefix: MacPrefix = "02:00:00:00:00:00/8".parse().unwrap();
+        let (lower, upper) = prefix.split().unwrap();
+        assert_eq!("02:00:00:00:00:00/9", format!("{:#}", lower));
+        assert_eq!("02:80:00:00:00:00/9", format!("{:#}", upper));
+        assert_eq!(Some(prefix), upper.supernet());
+        assert_eq!(Some(prefix), lower.supernet());
+        assert_eq!(
+            "00:00:00:00:00:00/6",
+            format!("{:#}", prefix.supernet().unwrap().supernet().unwrap())
+        );
+        assert_eq!(None, MacPrefix::from(MacAddress::nil()).split());
+        assert_eq!(
+            None,
+            MacPrefix::new(MacAddress::nil(), 0).unwrap().supernet()
+        );
+
+        let small = MacPrefix::new(MacAddress::new([2, 0, 0, 0, 0, 0xFF]), 46).unwrap();
+        assert_eq!(4, small.iter().size());
+        assert_eq!(
+            Some(MacAddress::new([2, 0, 0, 0, 0, 0xFF])),
+            small.into_iter().last()
+        );
+    }
+
+    #[test]
+    fn test_parse_str() {
+        use super::ParseError::*;
+
+        let prefix = MacPrefix::new(MacAddress::new([0x00, 0x11, 0x22, 0, 0, 0]), 24).unwrap();
+        assert_eq!(Ok(prefix), "00:11:22:00:00:00/24".parse());
+        assert_eq!(Ok(prefix), "00-11-22-33-44-55/24".parse());
+        assert_eq!(Ok(prefix), "00:11:22:00:00:00/ff:ff:ff:00:00:00".parse());
+        assert_eq!(Ok(prefix), "0011.2200.0000  ffff.ff00.0000".parse());
+
+        assert_eq!(Err(UnknownFormat), "00:11:22:00:00:00".parse::<MacPrefix>());
+        assert_eq!(
+            Err(InvalidPrefixLength(49)),
+            "00:11:22:00:00:00/49".parse::<MacPrefix>()
+        );
+        assert_eq!(
+            Err(InvalidPrefixLength(usize::MAX)),
+            "00:11:22:00:00:00/99999999999999999999999".parse::<MacPrefix>()
+        );
+        assert_eq!(
+            Err(InvalidPrefixLength(24)),
+            "00:11:22:00:00:00 24".parse::<MacPrefix>()
+        );
+        assert_eq!(
+            Err(InvalidPrefixLength(24)),
+            "00:11:22:00:00:00\u{3000}24".parse::<MacPrefix>()
+        );
+        assert_eq!(
+            Ok(prefix),
+            "00:11:22:00:00:00\u{a0}ff:ff:ff:00:00:00".parse()
+        );
+        assert_eq!(
+            Err(InvalidMask(MacAddress::new([0xFF, 0, 0xFF, 0, 0, 0]))),
+            "00:11:22:00:00:00/ff:00:ff:00:00:00".parse::<MacPrefix>()
+        );
+        assert_eq!(
+            Err(InvalidLength(0)),
+            "00:11:22:00:00:00/".parse::<MacPrefix>()
+        );
+        assert_eq!(
+            Err(InvalidByteCount(5, [0, 0x11, 0x22, 0, 0, 0])),
+            "00:11:22:00:00/24".parse::<MacPrefix>()
+        );
+    }
+
+    #[test]
+    fn test_fmt() {
+        let prefix: MacPrefix = "02:00:00:00:00:00/8".parse().unwrap();
+        assert_eq!(
+            "MacPrefix(\"02:00:00:00:00:00/8\")",
+            format!("{:?}", prefix)
+        );
+        assert_eq!("02:00:00:00:00:00/8 ", format!("{:<#20}", prefix));
+        match MacAddress::get_display_format() {
+            MacAddressFormat::HexString => assert_eq!("02:00:00:00:00:00/8", prefix.to_string()),
+            _ => assert_eq!("02-00-00-00-00-00/8", prefix.to_string()),
+        }
+        assert_eq!(
+            "Invalid prefix length; expecting 0 to 48, found 49",
+            ParseError::InvalidPrefixLength(49).to_string()
+        );
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let prefix: MacPrefix = "12:34:56:78:00:00/28".parse().unwrap();
+        for &default in MacAddressFormat::ALL {
+            let mut buf = [0; 20];
+            let s = prefix.write_to(&mut buf, default.parseable());
+            assert_eq!(Ok(prefix), s.parse(), "{:?} {}", default, s);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde_json", not(feature = "serde_bytes")))]
+    fn test_serde_json() {
+        use serde_json;
+
+        let prefix: MacPrefix = "00:11:22:00:00:00/24".parse().unwrap();
+        let json = serde_json::to_string(&prefix).unwrap();
+        assert_eq!(Ok(prefix), serde_json::from_str(&json).map_err(|_| ()));
+        assert!(serde_json::from_str::<MacPrefix>("\"00:11:22:00:00:00/49\"").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde_bytes")]
+    fn test_serde_bytes_serialization_roundtrip() {
+        let prefix: MacPrefix = "00:11:22:00:00:00/24".parse().unwrap();
+        let mut buffer = Vec::new();
+        bincode::serialize_into(&mut buffer, &prefix).unwrap();
+        let back: MacPrefix = bincode::deserialize_from(&*buffer).unwrap();
+        assert_eq!(prefix, back);
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Random MAC addresses, with the `rand` feature
+
+use rand::distributions::{Distribution, Standard};
+use rand::{Rng, RngCore};
+
+use super::bitwise::from_bits;
+use super::{MacAddress, MacPrefix};
+
+/// Generates random MAC addresses with the group and local bits chosen up front
+///
+/// The default distribution yields locally administered unicast addresses, the kind suitable
+/// for virtual machines and containers.
+///
+/// ```
+/// use eui48::{MacAddress, MacDistribution};
+/// use rand::Rng;
+///
+/// let mut rng = rand::thread_rng();
+/// let mac: MacAddress = rng.sample(MacDistribution::new());
+/// assert!(mac.is_local() && mac.is_unicast());
+///
+/// let group = MacDistribution::new().multicast(true).local(false);
+/// assert!(rng.sample(group).is_multicast());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacDistribution {
+    /// Set the group (I/G) bit
+    multicast: bool,
+    /// Set the locally administered (U/L) bit
+    local: bool,
+}
+
+impl MacDistribution {
+    /// Create a distribution of locally administered unicast addresses
+    pub const fn new() -> MacDistribution {
+        MacDistribution {
+            multicast: false,
+            local: true,
+        }
+    }
+
+    /// Yield multicast addresses if true, unicast addresses if false
+    pub const fn multicast(mut self, multicast: bool) -> MacDistribution {
+        self.multicast = multicast;
+        self
+    }
+
+    /// Yield locally administered addresses if true, universal addresses if false
+    pub const fn local(mut self, local: bool) -> MacDistribution {
+        self.local = local;
+        self
+    }
+}
+
+impl Default for MacDistribution {
+    fn default() -> MacDistribution {
+        MacDistribution::new()
+    }
+}
+
+impl Distribution<MacAddress> for MacDistribution {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> MacAddress {
+        let mut eui = rng.sample::<MacAddress, _>(Standard).to_array();
+        eui[0] = (eui[0] & !0x03) | (u8::from(self.local) << 1) | u8::from(self.multicast);
+        MacAddress::new(eui)
+    }
+}
+
+impl Distribution<MacAddress> for Standard {
+    /// Samples any of the 2^48 addresses, including multicast and broadcast
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> MacAddress {
+        from_bits(rng.next_u64())
+    }
+}
+
+impl MacAddress {
+    /// Returns a random locally administered unicast address from the thread-local generator
+    #[cfg(feature = "std")]
+    pub fn random_local_unicast() -> MacAddress {
+        MacAddress::random_local_unicast_with(&mut rand::thread_rng())
+    }
+
+    /// Returns a random locally administered unicast address from `rng`
+    pub fn random_local_unicast_with<R: RngCore + ?Sized>(rng: &mut R) -> MacAddress {
+        MacDistribution::new().sample(rng)
+    }
+
+    /// Returns a random address of `prefix` from the thread-local generator
+    #[cfg(feature = "std")]
+    pub fn random_in_prefix(prefix: &MacPrefix) -> MacAddress {
+        MacAddress::random_in_prefix_with(prefix, &mut rand::thread_rng())
+    }
+
+    /// Returns a random address of `prefix` from `rng`
+    ///
+    /// Only the bits after the prefix are random, so the group and local bits are random too
+    /// for prefixes shorter than 8 bits.
+    pub fn random_in_prefix_with<R: RngCore + ?Sized>(
+        prefix: &MacPrefix,
+        rng: &mut R,
+    ) -> MacAddress {
+        let host = rng.next_u64() & !prefix.mask().to_u64();
+        from_bits(prefix.network().to_u64() | host)
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_distribution() {
+        let mut rng = StepRng::new(0xFFFF_FFFF_FFFF_FFFF, 0x0123_4567_89AB_CDEF);
+        for _ in 0..16 {
+            let mac = MacAddress::random_local_unicast_with(&mut rng);
+            assert!(mac.is_local() && mac.is_unicast());
+            let mac: MacAddress = rng.sample(MacDistribution::new().multicast(true));
+            assert!(mac.is_local() && mac.is_multicast());
+            let mac: MacAddress = rng.sample(MacDistribution::new().local(false));
+            assert!(mac.is_universal() && mac.is_unicast());
+            let mac: MacAddress =
+                rng.sample(MacDistribution::default().local(false).multicast(true));
+            assert!(mac.is_universal() && mac.is_multicast());
+        }
+
+        let mut rng = StepRng::new(0x1234_5678_9ABC_DEF0, 0);
+        assert_eq!(
+            MacAddress::new([0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0]),
+            rng.gen::<MacAddress>()
+        );
+        assert_eq!(
+            MacAddress::new([0x55, 0x78, 0x9A, 0xBC, 0xDE, 0xF0]),
+            rng.sample(MacDistribution::new().local(false).multicast(true))
+        );
+    }
+
+    #[test]
+    fn test_random_in_prefix() {
+        let prefix: MacPrefix = "02:00:5e:10:00:00/28".parse().unwrap();
+        let mut rng = StepRng::new(u64::MAX, 0);
+        assert_eq!(
+            prefix.last(),
+            MacAddress::random_in_prefix_with(&prefix, &mut rng)
+        );
+        let mut rng = StepRng::new(0, 0);
+        assert_eq!(
+            prefix.network(),
+            MacAddress::random_in_prefix_with(&prefix, &mut rng)
+        );
+
+        let mut rng = StepRng::new(0, 0x0000_0123_4567_89AB);
+        for _ in 0..16 {
+            assert!(prefix.contains(&MacAddress::random_in_prefix_with(&prefix, &mut rng)));
+        }
+        let single = MacPrefix::from(MacAddress::broadcast());
+        assert_eq!(
+            MacAddress::broadcast(),
+            MacAddress::random_in_prefix_with(&single, &mut rng)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_thread_rng() {
+        let mac = MacAddress::random_local_unicast();
+        assert!(mac.is_local() && mac.is_unicast());
+        let prefix: MacPrefix = "0a:1b:2c:00:00:00/24".parse().unwrap();
+        assert!(prefix.contains(&MacAddress::random_in_prefix(&prefix)));
+    }
+}
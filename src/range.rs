@@ -0,0 +1,283 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Arithmetic on MacAddress values and iteration over ranges of addresses
+
+use core::convert::TryFrom;
+use core::iter::FusedIterator;
+use core::ops::RangeInclusive;
+
+use super::bitwise::from_bits;
+use super::MacAddress;
+
+/// Largest value of a 48-bit address
+const MAX: u64 = (1 << 48) - 1;
+
+impl MacAddress {
+    /// Returns the address `n` after this one, or None if it would pass ff-ff-ff-ff-ff-ff
+    pub const fn checked_add(&self, n: u64) -> Option<MacAddress> {
+        match self.to_u64().checked_add(n) {
+            Some(v) if v <= MAX => Some(from_bits(v)),
+            _ => None,
+        }
+    }
+
+    /// Returns the address `n` before this one, or None if it would pass 00-00-00-00-00-00
+    pub const fn checked_sub(&self, n: u64) -> Option<MacAddress> {
+        match self.to_u64().checked_sub(n) {
+            Some(v) => Some(from_bits(v)),
+            None => None,
+        }
+    }
+
+    /// Returns the address `n` after this one, wrapping around at ff-ff-ff-ff-ff-ff
+    pub const fn wrapping_add(&self, n: u64) -> MacAddress {
+        from_bits(self.to_u64().wrapping_add(n))
+    }
+
+    /// Returns the address `n` before this one, wrapping around at 00-00-00-00-00-00
+    pub const fn wrapping_sub(&self, n: u64) -> MacAddress {
+        from_bits(self.to_u64().wrapping_sub(n))
+    }
+
+    /// Returns the following address, or None for ff-ff-ff-ff-ff-ff
+    pub const fn next(&self) -> Option<MacAddress> {
+        self.checked_add(1)
+    }
+
+    /// Returns the preceding address, or None for 00-00-00-00-00-00
+    pub const fn prev(&self) -> Option<MacAddress> {
+        self.checked_sub(1)
+    }
+
+    /// Returns the number of addresses between this address and `other`, in either order
+    pub const fn distance(&self, other: &MacAddress) -> u64 {
+        self.to_u64().abs_diff(other.to_u64())
+    }
+}
+
+/// An inclusive range of MAC addresses, iterated in ascending order
+///
+/// `core::iter::Step` is unstable, so `a..=b` cannot iterate MacAddress values directly;
+/// convert it with `MacRange::from(a..=b)` instead. The range is empty if `start > end`.
+///
+/// ```
+/// use eui48::{MacAddress, MacRange};
+///
+/// let base = MacAddress::new([0x02, 0, 0, 0, 0, 0xfe]);
+/// let ports = MacRange::new(base, base.checked_add(3).unwrap());
+/// assert_eq!(4, ports.size());
+/// assert_eq!(
+///     vec!["02-00-00-00-00-fe", "02-00-00-00-00-ff", "02-00-00-00-01-00", "02-00-00-00-01-01"],
+///     ports.map(|mac| mac.to_canonical()).collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacRange {
+    start: MacAddress,
+    end: MacAddress,
+    /// Set once `start` has been yielded as the last address
+    exhausted: bool,
+}
+
+impl MacRange {
+    /// Create the range of addresses from `start` up to and including `end`
+    pub const fn new(start: MacAddress, end: MacAddress) -> MacRange {
+        MacRange {
+            start,
+            end,
+            exhausted: false,
+        }
+    }
+
+    /// Returns the first address not yet yielded from the front
+    pub const fn start(&self) -> MacAddress {
+        self.start
+    }
+
+    /// Returns the last address not yet yielded from the back
+    pub const fn end(&self) -> MacAddress {
+        self.end
+    }
+
+    /// Returns true if no addresses remain
+    pub const fn is_empty(&self) -> bool {
+        self.exhausted || self.start.to_u64() > self.end.to_u64()
+    }
+
+    /// Returns true if `mac` is one of the remaining addresses
+    pub const fn contains(&self, mac: &MacAddress) -> bool {
+        let v = mac.to_u64();
+        !self.is_empty() && self.start.to_u64() <= v && v <= self.end.to_u64()
+    }
+
+    /// Returns the number of remaining addresses
+    ///
+    /// Unlike `len`, which is only available on 64-bit targets, this covers the 2^48 addresses
+    /// of a full range on any target.
+    pub const fn size(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.end.to_u64() - self.start.to_u64() + 1
+        }
+    }
+}
+
+impl From<RangeInclusive<MacAddress>> for MacRange {
+    fn from(range: RangeInclusive<MacAddress>) -> MacRange {
+        let (start, end) = range.into_inner();
+        MacRange::new(start, end)
+    }
+}
+
+impl Iterator for MacRange {
+    type Item = MacAddress;
+
+    fn next(&mut self) -> Option<MacAddress> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<MacAddress> {
+        if (n as u64) >= self.size() {
+            self.exhausted = true;
+            return None;
+        }
+        let mac = from_bits(self.start.to_u64() + n as u64);
+        match mac.next() {
+            Some(next) if mac != self.end => self.start = next,
+            _ => self.exhausted = true,
+        }
+        Some(mac)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.size()) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn count(self) -> usize {
+        usize::try_from(self.size()).expect("address count overflows usize")
+    }
+
+    fn last(mut self) -> Option<MacAddress> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for MacRange {
+    fn next_back(&mut self) -> Option<MacAddress> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<MacAddress> {
+        if (n as u64) >= self.size() {
+            self.exhausted = true;
+            return None;
+        }
+        let mac = from_bits(self.end.to_u64() - n as u64);
+        match mac.prev() {
+            Some(prev) if mac != self.start => self.end = prev,
+            _ => self.exhausted = true,
+        }
+        Some(mac)
+    }
+}
+
+/// Only on 64-bit targets, as the size of a range may exceed `usize` on smaller ones
+#[cfg(target_pointer_width = "64")]
+impl ExactSizeIterator for MacRange {}
+
+impl FusedIterator for MacRange {}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+        assert_eq!(
+            Some(MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCE, 0x00])),
+            mac.checked_add(0x11)
+        );
+        assert_eq!(
+            Some(MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEE])),
+            mac.prev()
+        );
+        assert_eq!(Some(mac), mac.next().and_then(|m| m.prev()));
+        assert_eq!(None, MacAddress::broadcast().next());
+        assert_eq!(None, MacAddress::nil().prev());
+        assert_eq!(None, mac.checked_add(u64::MAX));
+        assert_eq!(None, mac.checked_sub(0x1234_56AB_CDF0));
+        assert_eq!(Some(MacAddress::nil()), mac.checked_sub(0x1234_56AB_CDEF));
+        assert_eq!(MacAddress::nil(), MacAddress::broadcast().wrapping_add(1));
+        assert_eq!(MacAddress::broadcast(), MacAddress::nil().wrapping_sub(1));
+        assert_eq!(mac, mac.wrapping_add(1 << 48));
+        assert_eq!(0x11, mac.distance(&mac.wrapping_add(0x11)));
+        assert_eq!(0x11, MacAddress::distance(&mac.wrapping_add(0x11), &mac));
+        assert_eq!(0, mac.distance(&mac));
+    }
+
+    #[test]
+    fn test_range() {
+        let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xFE]);
+        let end = mac.checked_add(3).unwrap();
+        let range = MacRange::from(mac..=end);
+        assert_eq!(4, range.size());
+        assert!(range.contains(&mac.next().unwrap()));
+        assert!(!range.contains(&end.next().unwrap()));
+        assert_eq!(
+            vec![
+                0x1234_56AB_CDFE,
+                0x1234_56AB_CDFF,
+                0x1234_56AB_CE00,
+                0x1234_56AB_CE01
+            ],
+            range.map(|m| m.to_u64()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![end, end.prev().unwrap()],
+            range.rev().take(2).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(end), range.last());
+        assert_eq!(Some(mac.wrapping_add(2)), range.clone().nth(2));
+        assert_eq!(None, range.clone().nth(4));
+
+        let mut range = range;
+        assert_eq!(Some(mac), range.next());
+        assert_eq!(Some(end), range.next_back());
+        assert_eq!(2, range.size());
+        assert_eq!(Some(mac.wrapping_add(1)), range.next());
+        assert_eq!(Some(mac.wrapping_add(2)), range.next_back());
+        assert_eq!(None, range.next());
+        assert_eq!(None, range.next_back());
+        assert!(range.is_empty());
+
+        assert_eq!(0, MacRange::new(end, mac).count());
+        assert_eq!(1, MacRange::new(mac, mac).count());
+        let all = MacRange::new(MacAddress::nil(), MacAddress::broadcast());
+        assert_eq!(1 << 48, all.size());
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(1 << 48, all.len());
+        assert_eq!(Some(MacAddress::broadcast()), all.last());
+        assert_eq!(
+            vec![MacAddress::broadcast()],
+            MacRange::new(MacAddress::broadcast(), MacAddress::broadcast()).collect::<Vec<_>>()
+        );
+        let mut top = MacRange::new(
+            MacAddress::broadcast().wrapping_sub(1),
+            MacAddress::broadcast(),
+        );
+        assert_eq!(Some(MacAddress::broadcast()), top.nth(1));
+        assert_eq!(None, top.next());
+    }
+}
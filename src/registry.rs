@@ -0,0 +1,582 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Vendor registries loaded at runtime from IEEE and Wireshark files
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use super::oui::longest_match;
+use super::{MacAddress, MacPrefix, Registry, Vendor, EUI48LEN};
+
+/// The file layouts an OuiRegistry can load
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OuiFileFormat {
+    /// The IEEE `oui.txt` listing, with '(hex)' and '(base 16)' lines and an indented address
+    OuiTxt,
+    /// The IEEE `oui.csv`, `mam.csv`, `oui36.csv`, `iab.csv` and `cid.csv` files
+    Csv,
+    /// Wireshark's `manuf` file, with '/28' and '/36' suffixes for the longer assignments
+    Manuf,
+}
+
+impl OuiFileFormat {
+    /// Guesses the format from a file name: `*.csv`, `*.txt` or `manuf`
+    pub fn from_path(path: &Path) -> Option<OuiFileFormat> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Some(OuiFileFormat::Csv),
+            Some("txt") => Some(OuiFileFormat::OuiTxt),
+            _ if path.file_stem().and_then(|s| s.to_str()) == Some("manuf") => {
+                Some(OuiFileFormat::Manuf)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Errors loading a registry file into an OuiRegistry
+#[derive(Debug)]
+pub enum OuiLoadError {
+    /// The file could not be read
+    Io(io::Error),
+    /// The format of the file could not be guessed from its name
+    UnknownFormat,
+    /// The line at the 1-based line number is malformed, for the reason given
+    InvalidLine(usize, &'static str),
+}
+
+impl From<io::Error> for OuiLoadError {
+    fn from(err: io::Error) -> OuiLoadError {
+        OuiLoadError::Io(err)
+    }
+}
+
+impl fmt::Display for OuiLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OuiLoadError::Io(err) => write!(f, "Cannot read registry; {}", err),
+            OuiLoadError::UnknownFormat => write!(f, "Unknown registry file format"),
+            OuiLoadError::InvalidLine(line, reason) => {
+                write!(f, "Invalid registry entry at line {}; {}", line, reason)
+            }
+        }
+    }
+}
+
+impl Error for OuiLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OuiLoadError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A vendor registry loaded at runtime, for when the compiled-in table is stale
+///
+/// Files loaded later take precedence over earlier ones for the same block, and lookups
+/// prefer a 28-bit or 36-bit assignment over the 24-bit block enclosing it, like
+/// `MacAddress::vendor`.
+///
+/// ```
+/// use eui48::{MacAddress, OuiFileFormat, OuiRegistry};
+///
+/// let manuf = "00:00:0C\tCisco\tCisco Systems, Inc\n\
+///              00:50:C2:00:10:00/36\tBerkeley\tBerkeley Process Control, Inc.\n";
+/// let mut registry = OuiRegistry::new();
+/// registry.load(manuf.as_bytes(), OuiFileFormat::Manuf).unwrap();
+///
+/// let mac = MacAddress::new([0x00, 0x00, 0x0c, 0x12, 0x34, 0x56]);
+/// assert_eq!("Cisco Systems, Inc", registry.lookup(&mac).unwrap().organization());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OuiRegistry {
+    /// Sorted by block, without duplicates
+    entries: Vec<Vendor>,
+}
+
+impl OuiRegistry {
+    /// Create an empty registry
+    pub fn new() -> OuiRegistry {
+        OuiRegistry::default()
+    }
+
+    /// Create a registry holding the compiled-in table, to load newer files over
+    #[cfg(feature = "oui-db")]
+    pub fn builtin() -> OuiRegistry {
+        OuiRegistry {
+            entries: super::oui::builtin().to_vec(),
+        }
+    }
+
+    /// Loads the entries of a registry file, returning how many were read
+    ///
+    /// Stops at the first malformed line and leaves the registry unchanged.
+    pub fn load<R: Read>(
+        &mut self,
+        reader: R,
+        format: OuiFileFormat,
+    ) -> Result<usize, OuiLoadError> {
+        let lines = read_lines(reader)?;
+        let mut loaded = match format {
+            OuiFileFormat::OuiTxt => parse_oui_txt(&lines)?,
+            OuiFileFormat::Csv => parse_csv(&lines)?,
+            OuiFileFormat::Manuf => parse_manuf(&lines)?,
+        };
+        let count = loaded.len();
+        // Reversed so the last entry of the file wins a duplicate, then older entries follow
+        loaded.reverse();
+        loaded.append(&mut self.entries);
+        loaded.sort_by_key(|v| v.block());
+        loaded.dedup_by_key(|v| v.block());
+        self.entries = loaded;
+        Ok(count)
+    }
+
+    /// Loads a registry file, guessing its format with `OuiFileFormat::from_path`
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, OuiLoadError> {
+        let path = path.as_ref();
+        let format = OuiFileFormat::from_path(path).ok_or(OuiLoadError::UnknownFormat)?;
+        self.load(File::open(path)?, format)
+    }
+
+    /// Returns the organization the address was assigned to
+    ///
+    /// The entry is the same `Vendor` that `MacAddress::vendor` returns from the compiled-in
+    /// table, borrowed from this registry instead.
+    pub fn lookup(&self, mac: &MacAddress) -> Option<&Vendor> {
+        longest_match(&self.entries, mac)
+    }
+
+    /// Returns the number of assignments in the registry
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the registry holds no assignments
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the assignments ordered by block
+    pub fn iter(&self) -> std::slice::Iter<'_, Vendor> {
+        self.entries.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a OuiRegistry {
+    type Item = &'a Vendor;
+    type IntoIter = std::slice::Iter<'a, Vendor>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Reads the lines of a file, reporting invalid UTF-8 with its line number
+fn read_lines<R: Read>(reader: R) -> Result<Vec<String>, OuiLoadError> {
+    let mut reader = BufReader::new(reader);
+    let mut lines = Vec::new();
+    let mut buf = Vec::new();
+    while reader.read_until(b'\n', &mut buf)? > 0 {
+        let line = String::from_utf8(std::mem::take(&mut buf))
+            .map_err(|_| OuiLoadError::InvalidLine(lines.len() + 1, "invalid UTF-8"))?;
+        let line = line.trim_end_matches(['\n', '\r']);
+        lines.push(line.trim_start_matches('\u{feff}').to_string());
+    }
+    Ok(lines)
+}
+
+/// Create the block of an assignment of 6, 7 or 9 hex digits, like '00000C'
+fn parse_assignment(digits: &str) -> Option<MacPrefix> {
+    if !matches!(digits.len(), 6 | 7 | 9) {
+        return None;
+    }
+    let value = u64::from_str_radix(digits, 16).ok()?;
+    let prefix_len = 4 * digits.len() as u8;
+    let mac = MacAddress::from_u64(value << (48 - prefix_len)).ok()?;
+    MacPrefix::new(mac, prefix_len)
+}
+
+/// Create the block of an OUI like '00:00:0C', or of an address with a suffix like '/28'
+fn parse_block(s: &str) -> Option<MacPrefix> {
+    let (addr, prefix_len) = match s.split_once('/') {
+        Some((addr, len)) => (addr, Some(len.parse().ok()?)),
+        None => (s, None),
+    };
+    let mut eui = [0; EUI48LEN];
+    let mut count = 0;
+    for octet in addr.split([':', '-', '.']) {
+        if count == EUI48LEN || octet.len() != 2 {
+            return None;
+        }
+        eui[count] = u8::from_str_radix(octet, 16).ok()?;
+        count += 1;
+    }
+    let prefix_len = match prefix_len {
+        Some(prefix_len) if count == EUI48LEN => prefix_len,
+        None if count == 3 => 24,
+        _ => return None,
+    };
+    MacPrefix::new(MacAddress::new(eui), prefix_len)
+}
+
+/// Returns the registry that assigns blocks of `prefix_len` bits
+fn registry_for(prefix_len: u8) -> Registry {
+    match prefix_len {
+        28 => Registry::MaM,
+        36 => Registry::MaS,
+        _ => Registry::MaL,
+    }
+}
+
+/// Parses entries like '00-00-0C   (hex)\t\tCisco Systems, Inc' followed by address lines
+fn parse_oui_txt(lines: &[String]) -> Result<Vec<Vendor>, OuiLoadError> {
+    let mut entries = Vec::new();
+    let mut current: Option<(MacPrefix, String, Vec<&str>)> = None;
+    let mut flush = |current: &mut Option<(MacPrefix, String, Vec<&str>)>| {
+        if let Some((block, organization, address)) = current.take() {
+            let address = address.join(" ");
+            entries.push(Vendor::new_owned(
+                Registry::MaL,
+                block,
+                organization,
+                address,
+            ));
+        }
+    };
+    for (n, line) in lines.iter().enumerate() {
+        if let Some(pos) = line.find("(hex)") {
+            flush(&mut current);
+            let block = parse_block(line[..pos].trim())
+                .filter(|block| block.prefix_len() == 24)
+                .ok_or(OuiLoadError::InvalidLine(n + 1, "invalid assignment"))?;
+            current = Some((block, line[pos + 5..].trim().to_string(), Vec::new()));
+        } else if line.trim().is_empty() {
+            flush(&mut current);
+        } else if let Some((_, _, address)) = current.as_mut() {
+            if !line.contains("(base 16)") {
+                address.extend(line.split_whitespace());
+            }
+        }
+    }
+    flush(&mut current);
+    Ok(entries)
+}
+
+/// Parses 'Registry,Assignment,Organization Name[,Organization Address]' rows
+fn parse_csv(lines: &[String]) -> Result<Vec<Vendor>, OuiLoadError> {
+    let mut entries = Vec::new();
+    for (n, line) in lines.iter().enumerate() {
+        if line.is_empty() || (n == 0 && line.starts_with("Registry,")) {
+            continue;
+        }
+        let invalid = |reason| OuiLoadError::InvalidLine(n + 1, reason);
+        let fields = split_csv(line).ok_or_else(|| invalid("unterminated quote"))?;
+        if !matches!(fields.len(), 3 | 4) {
+            return Err(invalid("expecting 3 or 4 fields"));
+        }
+        let registry = match fields[0].as_str() {
+            "MA-L" => Registry::MaL,
+            "MA-M" => Registry::MaM,
+            "MA-S" => Registry::MaS,
+            "IAB" => Registry::Iab,
+            "CID" => Registry::Cid,
+            _ => return Err(invalid("unknown registry")),
+        };
+        let block = parse_assignment(fields[1].trim())
+            .filter(|block| block.prefix_len() == registry.prefix_len())
+            .ok_or_else(|| invalid("invalid assignment"))?;
+        let mut fields = fields.into_iter().skip(2).map(|s| s.trim().to_string());
+        let organization = fields.next().unwrap_or_default();
+        let address = fields.next().unwrap_or_default();
+        entries.push(Vendor::new_owned(registry, block, organization, address));
+    }
+    Ok(entries)
+}
+
+/// Splits a CSV line, honouring quoted fields and doubled quotes within them
+fn split_csv(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+/// Parses 'block<TAB>short name[<TAB>long name]' lines, skipping '#' comments
+///
+/// A '#' only starts a comment at the start of a line or after whitespace, as organization
+/// names may contain one.
+///
+/// The file does not name the registry, so it is inferred from the prefix length, and 36-bit
+/// IAB blocks are reported as MA-S.
+fn parse_manuf(lines: &[String]) -> Result<Vec<Vendor>, OuiLoadError> {
+    let mut entries = Vec::new();
+    for (n, line) in lines.iter().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\t').map(str::trim).filter(|s| !s.is_empty());
+        let block = fields
+            .next()
+            .and_then(parse_block)
+            .ok_or(OuiLoadError::InvalidLine(n + 1, "invalid assignment"))?;
+        let short = fields
+            .next()
+            .ok_or(OuiLoadError::InvalidLine(n + 1, "missing name"))?;
+        let organization = fields.next().unwrap_or(short).to_string();
+        let registry = registry_for(block.prefix_len());
+        entries.push(Vendor::new_owned(
+            registry,
+            block,
+            organization,
+            String::new(),
+        ));
+    }
+    Ok(entries)
+}
+
+/// Returns `line` up to a '#' at its start or after whitespace
+fn strip_comment(line: &str) -> &str {
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        if c == '#' && prev.is_whitespace() {
+            return &line[..i];
+        }
+        prev = c;
+    }
+    line
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac(s: &str) -> MacAddress {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_load_oui_txt() {
+        let text = "OUI/MA-L\t\t\tOrganization\r\n\
+                    company_id\t\t\tOrganization\r\n\
+                    \t\t\t\tAddress\r\n\
+                    \r\n\
+                    00-00-0C   (hex)\t\tCisco Systems, Inc\r\n\
+                    00000C     (base 16)\t\tCisco Systems, Inc\r\n\
+                    \t\t\t\t170 West Tasman Dr.\r\n\
+                    \t\t\t\tSan Jose  CA  95134\r\n\
+                    \t\t\t\tUS\r\n\
+                    \r\n\
+                    00-50-56   (hex)\t\tVMware, Inc.\r\n\
+                    005056     (base 16)\t\tVMware, Inc.\r\n";
+        let mut registry = OuiRegistry::new();
+        assert_eq!(
+            2,
+            registry
+                .load(text.as_bytes(), OuiFileFormat::OuiTxt)
+                .unwrap()
+        );
+        let cisco = registry.lookup(&mac("00:00:0c:12:34:56")).unwrap();
+        assert_eq!("Cisco Systems, Inc", cisco.organization());
+        assert_eq!("170 West Tasman Dr. San Jose CA 95134 US", cisco.address());
+        assert_eq!(Registry::MaL, cisco.registry());
+        assert_eq!(
+            "",
+            registry
+                .lookup(&mac("00:50:56:00:00:01"))
+                .unwrap()
+                .address()
+        );
+
+        let bad = "\n00-00-0G   (hex)\t\tNobody\n";
+        match registry.load(bad.as_bytes(), OuiFileFormat::OuiTxt) {
+            Err(OuiLoadError::InvalidLine(2, "invalid assignment")) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(2, registry.len());
+    }
+
+    #[test]
+    fn test_load_csv() {
+        let csv = "\u{feff}Registry,Assignment,Organization Name,Organization Address\n\
+                   MA-L,70B3D5,IEEE Registration Authority,445 Hoes Lane Piscataway NJ US 08554\n\
+                   MA-S,70B3D5123,\"Example \"\"Small\"\", Inc.\",\"1 Main St, Springfield\"\n\
+                   MA-M,70B3D51,Example Medium,\n\
+                   CID,0A1B2C,Example CID\n";
+        let mut registry = OuiRegistry::new();
+        assert_eq!(
+            4,
+            registry.load(csv.as_bytes(), OuiFileFormat::Csv).unwrap()
+        );
+        let small = registry.lookup(&mac("70:b3:d5:12:34:56")).unwrap();
+        assert_eq!("Example \"Small\", Inc.", small.organization());
+        assert_eq!("1 Main St, Springfield", small.address());
+        assert_eq!("70:b3:d5:12:30:00/36", format!("{:#}", small.block()));
+        assert_eq!(
+            "Example Medium",
+            registry
+                .lookup(&mac("70:b3:d5:1f:ff:ff"))
+                .unwrap()
+                .organization()
+        );
+        assert_eq!(
+            "IEEE Registration Authority",
+            registry
+                .lookup(&mac("70:b3:d5:20:00:00"))
+                .unwrap()
+                .organization()
+        );
+        assert_eq!(
+            Registry::Cid,
+            registry
+                .lookup(&mac("0a:1b:2c:00:00:00"))
+                .unwrap()
+                .registry()
+        );
+
+        for (line, reason) in [
+            ("MA-X,000000,A,B", "unknown registry"),
+            ("MA-M,000000,A,B", "invalid assignment"),
+            ("MA-L,00000G,A,B", "invalid assignment"),
+            ("MA-L,000000", "expecting 3 or 4 fields"),
+            ("MA-L,000000,A,B,C", "expecting 3 or 4 fields"),
+            ("MA-L,000000,\"A,B", "unterminated quote"),
+        ] {
+            let text = format!("MA-L,000001,Fine,\n{}\n", line);
+            match registry.load(text.as_bytes(), OuiFileFormat::Csv) {
+                Err(OuiLoadError::InvalidLine(2, r)) => assert_eq!(reason, r),
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        assert_eq!(4, registry.len());
+    }
+
+    #[test]
+    fn test_load_manuf() {
+        let manuf = "# Wireshark manuf\n\
+                     00:00:0C\tCisco\tCisco Systems, Inc\n\
+                     00:00:0D\tFibronic  # comment\n\
+                     00:00:0E\tHash#Tag\tHash#Tag Networks\t# comment\n\
+                     00:50:C2:00:10:00/36\tBerkeley\tBerkeley Process Control, Inc.\n\
+                     70:B3:D5:10:00:00/28\tMedium\n";
+        let mut registry = OuiRegistry::new();
+        assert_eq!(
+            5,
+            registry
+                .load(manuf.as_bytes(), OuiFileFormat::Manuf)
+                .unwrap()
+        );
+        assert_eq!(
+            "Fibronic",
+            registry
+                .lookup(&mac("00:00:0d:00:00:00"))
+                .unwrap()
+                .organization()
+        );
+        assert_eq!(
+            "Hash#Tag Networks",
+            registry
+                .lookup(&mac("00:00:0e:00:00:00"))
+                .unwrap()
+                .organization()
+        );
+        let berkeley = registry.lookup(&mac("00:50:c2:00:1a:bc")).unwrap();
+        assert_eq!(Registry::MaS, berkeley.registry());
+        assert_eq!(None, registry.lookup(&mac("00:50:c2:00:20:00")));
+        assert_eq!(
+            Registry::MaM,
+            registry
+                .lookup(&mac("70:b3:d5:1a:bc:de"))
+                .unwrap()
+                .registry()
+        );
+
+        // Later files override earlier ones for the same block
+        let csv = "MA-L,00000C,\"Cisco Systems, Inc\",170 West Tasman Dr.\n";
+        registry.load(csv.as_bytes(), OuiFileFormat::Csv).unwrap();
+        assert_eq!(5, registry.len());
+        let cisco = registry.lookup(&mac("00:00:0c:00:00:00")).unwrap();
+        assert_eq!("170 West Tasman Dr.", cisco.address());
+        assert!(registry
+            .iter()
+            .zip(registry.iter().skip(1))
+            .all(|(a, b)| a.block() < b.block()));
+
+        match registry.load("00:00:0E\n".as_bytes(), OuiFileFormat::Manuf) {
+            Err(OuiLoadError::InvalidLine(1, "missing name")) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+        match registry.load("00:00\tShort\n".as_bytes(), OuiFileFormat::Manuf) {
+            Err(OuiLoadError::InvalidLine(1, "invalid assignment")) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+        match registry.load(&b"\n\xff\n"[..], OuiFileFormat::Manuf) {
+            Err(err) => assert_eq!(
+                "Invalid registry entry at line 2; invalid UTF-8",
+                err.to_string()
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            Some(OuiFileFormat::Csv),
+            OuiFileFormat::from_path(Path::new("/var/lib/oui36.csv"))
+        );
+        assert_eq!(
+            Some(OuiFileFormat::OuiTxt),
+            OuiFileFormat::from_path(Path::new("oui.txt"))
+        );
+        assert_eq!(
+            Some(OuiFileFormat::Manuf),
+            OuiFileFormat::from_path(Path::new("/usr/share/wireshark/manuf"))
+        );
+        assert_eq!(None, OuiFileFormat::from_path(Path::new("oui.json")));
+        match OuiRegistry::new().load_file("oui.json") {
+            Err(OuiLoadError::UnknownFormat) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "oui-db")]
+    fn test_builtin() {
+        let mut registry = OuiRegistry::builtin();
+        assert!(registry.iter().eq(super::super::oui::builtin()));
+        let mac = mac("00:50:56:00:00:01");
+        assert_eq!(mac.vendor(), registry.lookup(&mac));
+        assert_ne!("", registry.lookup(&mac).unwrap().address());
+        let csv = "MA-L,005056,\"VMware, LLC\",\n";
+        registry.load(csv.as_bytes(), OuiFileFormat::Csv).unwrap();
+        assert_eq!("VMware, LLC", registry.lookup(&mac).unwrap().organization());
+    }
+}
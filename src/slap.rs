@@ -0,0 +1,182 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! IEEE 802c Structured Local Address Plan quadrants of locally administered addresses
+
+use core::fmt;
+
+use super::{Cid, MacAddress};
+
+/// Mask of the Z, Y and X bits of the first octet
+const SLAP_MASK: u8 = 0x0E;
+
+/// The IEEE 802c quadrant of a locally administered address, chosen by the Z and Y bits
+///
+/// The quadrant shows in the second hex digit of the address: 'x2' AAI, 'x6' Reserved,
+/// 'xA' ELI and 'xE' SAI, or one more for multicast addresses.
+///
+/// ```
+/// use eui48::{Cid, MacAddress, SlapQuadrant};
+///
+/// let cid = Cid::new([0x0a, 0x1b, 0x2c]).unwrap();
+/// let eli = MacAddress::from_eli(cid, 0x000001).unwrap();
+/// assert_eq!(Some(SlapQuadrant::Eli), eli.slap_quadrant());
+/// assert_eq!(Some(cid), eli.eli_cid());
+/// assert_eq!(None, MacAddress::new([0x00, 0x1b, 0x21, 0, 0, 1]).slap_quadrant());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SlapQuadrant {
+    /// Administratively Assigned Identifier, for local assignment by an administrator or protocol
+    Aai,
+    /// Reserved for future use by the IEEE
+    Reserved,
+    /// Extended Local Identifier, a Company ID followed by bits assigned by its owner
+    Eli,
+    /// Standard Assigned Identifier, assigned by a protocol specified in an IEEE 802 standard
+    Sai,
+}
+
+impl SlapQuadrant {
+    /// Returns the quadrant of a locally administered first octet, or None if it is universal
+    pub const fn from_octet(octet: u8) -> Option<SlapQuadrant> {
+        match octet & SLAP_MASK {
+            0x02 => Some(SlapQuadrant::Aai),
+            0x06 => Some(SlapQuadrant::Reserved),
+            0x0A => Some(SlapQuadrant::Eli),
+            0x0E => Some(SlapQuadrant::Sai),
+            _ => None,
+        }
+    }
+
+    /// Returns the Z, Y and X bits of the quadrant, as found in the first octet
+    pub const fn bits(&self) -> u8 {
+        match self {
+            SlapQuadrant::Aai => 0x02,
+            SlapQuadrant::Reserved => 0x06,
+            SlapQuadrant::Eli => 0x0A,
+            SlapQuadrant::Sai => 0x0E,
+        }
+    }
+}
+
+impl fmt::Display for SlapQuadrant {
+    /// Display format is the abbreviation used by IEEE 802c, such as 'ELI'
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            SlapQuadrant::Aai => "AAI",
+            SlapQuadrant::Reserved => "Reserved",
+            SlapQuadrant::Eli => "ELI",
+            SlapQuadrant::Sai => "SAI",
+        })
+    }
+}
+
+impl MacAddress {
+    /// Returns the SLAP quadrant of a locally administered address, or None if it is universal
+    pub const fn slap_quadrant(&self) -> Option<SlapQuadrant> {
+        SlapQuadrant::from_octet(self.to_be_bytes()[0])
+    }
+
+    /// Returns the address moved into `quadrant`, setting the local bit and the Z and Y bits
+    ///
+    /// The group bit and the remaining bits are kept.
+    pub const fn with_slap_quadrant(&self, quadrant: SlapQuadrant) -> MacAddress {
+        let mut eui = self.to_be_bytes();
+        eui[0] = (eui[0] & !SLAP_MASK) | quadrant.bits();
+        MacAddress::new(eui)
+    }
+
+    /// Create an ELI address from the Company ID and the 24-bit suffix assigned by its owner
+    ///
+    /// Returns None if `cid` is not in the ELI quadrant or `suffix` does not fit in 24 bits.
+    pub const fn from_eli(cid: Cid, suffix: u32) -> Option<MacAddress> {
+        if !matches!(cid.quadrant(), SlapQuadrant::Eli) || suffix >> 24 != 0 {
+            return None;
+        }
+        Some(cid.with_nic(suffix))
+    }
+
+    /// Returns the Company ID of an ELI unicast address, or None for any other address
+    pub const fn eli_cid(&self) -> Option<Cid> {
+        match self.slap_quadrant() {
+            Some(SlapQuadrant::Eli) => self.cid(),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the address is locally administered in the AAI quadrant
+    pub const fn is_aai(&self) -> bool {
+        matches!(self.slap_quadrant(), Some(SlapQuadrant::Aai))
+    }
+
+    /// Returns true if the address is locally administered in the ELI quadrant
+    pub const fn is_eli(&self) -> bool {
+        matches!(self.slap_quadrant(), Some(SlapQuadrant::Eli))
+    }
+
+    /// Returns true if the address is locally administered in the SAI quadrant
+    pub const fn is_sai(&self) -> bool {
+        matches!(self.slap_quadrant(), Some(SlapQuadrant::Sai))
+    }
+}
+
+impl Cid {
+    /// Returns the SLAP quadrant of the Company ID; the IEEE assigns new ones in ELI
+    pub const fn quadrant(&self) -> SlapQuadrant {
+        match SlapQuadrant::from_octet(self.to_bytes()[0]) {
+            Some(quadrant) => quadrant,
+            None => panic!("a Company ID is locally administered"),
+        }
+    }
+}
+
+// ************** TESTS BEGIN HERE ***************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadrants() {
+        let quadrant = |b: u8| MacAddress::new([b, 0, 0, 0, 0, 0]).slap_quadrant();
+        assert_eq!(None, quadrant(0x00));
+        assert_eq!(None, quadrant(0xAD));
+        assert_eq!(Some(SlapQuadrant::Aai), quadrant(0x02));
+        assert_eq!(Some(SlapQuadrant::Aai), quadrant(0xF3));
+        assert_eq!(Some(SlapQuadrant::Reserved), quadrant(0x16));
+        assert_eq!(Some(SlapQuadrant::Eli), quadrant(0x2A));
+        assert_eq!(Some(SlapQuadrant::Sai), quadrant(0x3F));
+
+        let mac = MacAddress::new([0x01, 0x23, 0x45, 0x67, 0x89, 0xAB]);
+        let aai = mac.with_slap_quadrant(SlapQuadrant::Aai);
+        assert_eq!(MacAddress::new([0x03, 0x23, 0x45, 0x67, 0x89, 0xAB]), aai);
+        assert!(aai.is_aai() && aai.is_multicast());
+        let sai = aai.with_slap_quadrant(SlapQuadrant::Sai);
+        assert_eq!(MacAddress::new([0x0F, 0x23, 0x45, 0x67, 0x89, 0xAB]), sai);
+        assert!(sai.is_sai() && !sai.is_eli() && !mac.is_aai());
+        assert_eq!(0x0A, SlapQuadrant::Eli.bits());
+        assert_eq!("ELI", SlapQuadrant::Eli.to_string());
+        assert_eq!("Reserved", SlapQuadrant::Reserved.to_string());
+    }
+
+    #[test]
+    fn test_eli() {
+        let cid = Cid::new([0x1A, 0x2B, 0x3C]).unwrap();
+        assert_eq!(SlapQuadrant::Eli, cid.quadrant());
+        let eli = MacAddress::from_eli(cid, 0xABCDEF).unwrap();
+        assert_eq!(MacAddress::new([0x1A, 0x2B, 0x3C, 0xAB, 0xCD, 0xEF]), eli);
+        assert!(eli.is_eli());
+        assert_eq!(Some(cid), eli.eli_cid());
+        assert_eq!(None, MacAddress::new([0x1B, 0x2B, 0x3C, 0, 0, 0]).eli_cid());
+        assert_eq!(None, MacAddress::new([0x12, 0x2B, 0x3C, 0, 0, 0]).eli_cid());
+
+        assert_eq!(None, MacAddress::from_eli(cid, 1 << 24));
+        let aai = Cid::new([0x12, 0x2B, 0x3C]).unwrap();
+        assert_eq!(SlapQuadrant::Aai, aai.quadrant());
+        assert_eq!(None, MacAddress::from_eli(aai, 0));
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2016 Andrew Baumhauer <andy@baumhauer.us>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialization under a BitReversed default format
+//!
+//! The default format is process wide, so these tests live in their own test binary rather
+//! than next to the unit tests relying on the initial default.
+
+#[test]
+#[cfg(all(
+    feature = "serde",
+    feature = "serde_json",
+    not(feature = "serde_bytes")
+))]
+fn test_bit_reversed_default_serde_json() {
+    use eui48::{Eui64Address, MacAddress, MacAddressFormat, MacPrefix};
+
+    MacAddress::set_default_format(MacAddressFormat::BitReversed);
+
+    let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+    let json = serde_json::to_string(&mac).unwrap();
+    assert_eq!("\"12:34:56:ab:cd:ef\"", json);
+    assert_eq!(mac, serde_json::from_str::<MacAddress>(&json).unwrap());
+
+    let eui = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+    let json = serde_json::to_string(&eui).unwrap();
+    assert_eq!(eui, serde_json::from_str::<Eui64Address>(&json).unwrap());
+
+    let prefix: MacPrefix = "12:34:56:00:00:00/24".parse().unwrap();
+    let json = serde_json::to_string(&prefix).unwrap();
+    assert_eq!(prefix, serde_json::from_str::<MacPrefix>(&json).unwrap());
+}
+
+#[test]
+#[cfg(feature = "rustc-serialize")]
+fn test_bit_reversed_default_rustc_serialize() {
+    use eui48::{Eui64Address, MacAddress, MacAddressFormat};
+    use rustc_serialize::json;
+
+    MacAddress::set_default_format(MacAddressFormat::BitReversed);
+
+    let mac = MacAddress::new([0x12, 0x34, 0x56, 0xAB, 0xCD, 0xEF]);
+    let encoded = json::encode(&mac).unwrap();
+    assert_eq!("\"12:34:56:ab:cd:ef\"", encoded);
+    assert_eq!(mac, json::decode::<MacAddress>(&encoded).unwrap());
+
+    let eui = Eui64Address::new([0x12, 0x34, 0x56, 0xFF, 0xFE, 0xAB, 0xCD, 0xEF]);
+    let encoded = json::encode(&eui).unwrap();
+    assert_eq!(eui, json::decode::<Eui64Address>(&encoded).unwrap());
+}